@@ -0,0 +1,397 @@
+//! Backtesting: replay a recorded event stream through [`StrategyEngine`].
+//!
+//! Fills go through the engine exactly as they would live (strategies,
+//! merging, risk limits). The resulting plans are executed against the
+//! replayed pool state after `latency_ms`, using constant-product DEX math,
+//! the venue's pool fee and an extra slippage haircut. Our own trades move
+//! the simulated reserves. Positions live in an in-memory `PositionManager`,
+//! and `positions::unix_timestamp` follows the replayed clock, so time-based
+//! exits (`max_hold`, stale positions) fire on event time.
+//!
+//! Limits: cooldowns and windows measured with `Instant` (risk cooldowns,
+//! `consensus_buy` windows, tick exit cooldowns) still run on wall-clock
+//! time. The engine handle is process-global, so run one backtest per
+//! process.
+
+pub mod replay;
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    io::Write,
+    path::Path,
+    sync::Arc,
+};
+
+use crate::config::settings::Settings;
+use crate::dex::settings_for_plan;
+use crate::paper::{pool_fee_bps, quote_out};
+use crate::positions::{
+    ledger::FillMeta, set_simulated_time, wallet_stats::WalletStats, PositionManager,
+};
+use crate::strategy::engine::{EngineShared, StrategyEngine, STRATEGY_ENGINE};
+use crate::strategy::{DexKind, Side, TradePlan};
+use replay::{parse_mint, ReplayEvent};
+
+/// Execution model of a backtest.
+#[derive(Clone, Debug)]
+pub struct BacktestConfig {
+    /// Delay between a plan and its fill
+    pub latency_ms: u64,
+    /// Extra adverse price move on every fill, on top of pool fee and impact
+    pub slippage_bps: u64,
+    /// Charge the bribe and priority fee of the plan's settings
+    pub include_tips: bool,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            latency_ms: 400,
+            slippage_bps: 100,
+            include_tips: true,
+        }
+    }
+}
+
+/// One simulated fill.
+#[derive(Clone, Debug, Serialize)]
+pub struct BacktestTrade {
+    pub ts_ms: u64,
+    pub mint: String,
+    pub side: Side,
+    pub strategy: Option<String>,
+    pub wallet_label: Option<String>,
+    pub tokens: u64,
+    /// Paid (buy, incl. tips) or received (sell, net of tips)
+    pub lamports: u64,
+    /// Fill price in lamports per base unit
+    pub price: f64,
+}
+
+/// Portfolio value after a trade.
+#[derive(Clone, Debug, Serialize)]
+pub struct PnlPoint {
+    pub ts_ms: u64,
+    pub realized_lamports: i64,
+    pub unrealized_lamports: i64,
+    pub open_positions: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct BacktestReport {
+    pub trades: Vec<BacktestTrade>,
+    pub pnl_curve: Vec<PnlPoint>,
+    pub wallet_stats: Vec<(String, WalletStats)>,
+    /// Plans that could not be filled (no price, nothing held)
+    pub skipped: usize,
+}
+
+/// Replayed state of one mint's market.
+#[derive(Clone, Copy, Debug, Default)]
+struct Market {
+    /// (venue, token reserve, SOL reserve)
+    reserves: Option<(DexKind, u64, u64)>,
+    /// Lamports per base unit
+    price: Option<f64>,
+}
+
+struct Runner<'a> {
+    settings: &'a Settings,
+    cfg: &'a BacktestConfig,
+    shared: Arc<EngineShared>,
+    markets: HashMap<Pubkey, Market>,
+    /// (execute at, plan), in time order
+    pending: VecDeque<(u64, TradePlan)>,
+    realized_lamports: i64,
+    report: BacktestReport,
+}
+
+/// Replay `events` (ordered by time) with `settings` and return the report.
+pub fn run(
+    settings: &Settings,
+    events: &[ReplayEvent],
+    cfg: &BacktestConfig,
+) -> Result<BacktestReport> {
+    let shared = Arc::new(EngineShared::new(PositionManager::in_memory()));
+    let mut engine = StrategyEngine::from_settings(shared.clone(), settings)?;
+    if !STRATEGY_ENGINE
+        .get()
+        .map_or(false, |global| Arc::ptr_eq(global, &shared))
+    {
+        bail!("a strategy engine already exists in this process – run one backtest per process");
+    }
+
+    let mut runner = Runner {
+        settings,
+        cfg,
+        shared,
+        markets: HashMap::new(),
+        pending: VecDeque::new(),
+        realized_lamports: 0,
+        report: BacktestReport::default(),
+    };
+    let result = runner.replay(&mut engine, events);
+    set_simulated_time(None);
+    result?;
+
+    let pm = runner.shared.positions.lock().unwrap();
+    let mut stats: Vec<(String, WalletStats)> = pm
+        .wallet_stats()
+        .iter()
+        .map(|(label, s)| (label.clone(), s.clone()))
+        .collect();
+    stats.sort_by(|a, b| a.0.cmp(&b.0));
+    drop(pm);
+    runner.report.wallet_stats = stats;
+    Ok(runner.report)
+}
+
+impl Runner<'_> {
+    fn replay(&mut self, engine: &mut StrategyEngine, events: &[ReplayEvent]) -> Result<()> {
+        let tick_ms = self.settings.strategy_tick_ms.max(50);
+        let mut next_tick = events.first().map_or(0, |e| e.ts_ms());
+
+        for ev in events {
+            let ts = ev.ts_ms();
+            while next_tick <= ts {
+                self.advance(next_tick)?;
+                let plans = engine.on_tick(self.settings);
+                self.queue(next_tick, plans);
+                next_tick += tick_ms;
+            }
+            self.advance(ts)?;
+
+            match ev {
+                ReplayEvent::Fill { fill, .. } => {
+                    let fill = fill.to_observed()?;
+                    let plans = engine.on_fill(&fill, self.settings);
+                    self.queue(ts, plans);
+                }
+                ReplayEvent::Pool {
+                    mint,
+                    dex,
+                    token_reserve,
+                    sol_reserve,
+                    ..
+                } => {
+                    let mint = parse_mint(mint)?;
+                    let market = self.markets.entry(mint).or_default();
+                    market.reserves = Some((*dex, *token_reserve, *sol_reserve));
+                    if *token_reserve > 0 {
+                        let price = *sol_reserve as f64 / *token_reserve as f64;
+                        market.price = Some(price);
+                        self.shared
+                            .positions
+                            .lock()
+                            .unwrap()
+                            .update_price(mint, price)?;
+                    }
+                }
+                ReplayEvent::Price { mint, price, .. } => {
+                    let mint = parse_mint(mint)?;
+                    self.markets.entry(mint).or_default().price = Some(*price);
+                    self.shared
+                        .positions
+                        .lock()
+                        .unwrap()
+                        .update_price(mint, *price)?;
+                }
+            }
+        }
+
+        // let the last plans land
+        if let Some(&(last, _)) = self.pending.back() {
+            self.advance(last)?;
+        }
+        Ok(())
+    }
+
+    fn queue(&mut self, ts: u64, plans: Vec<TradePlan>) {
+        for plan in plans {
+            self.pending.push_back((ts + self.cfg.latency_ms, plan));
+        }
+    }
+
+    /// Move the clock to `ts` and fill every plan due by then.
+    fn advance(&mut self, ts: u64) -> Result<()> {
+        set_simulated_time(Some((ts / 1000).max(1)));
+        while self.pending.front().map_or(false, |(at, _)| *at <= ts) {
+            let (at, plan) = self.pending.pop_front().unwrap();
+            set_simulated_time(Some((at / 1000).max(1)));
+            self.fill(at, &plan)?;
+        }
+        set_simulated_time(Some((ts / 1000).max(1)));
+        Ok(())
+    }
+
+    /// Ledger details of `plan`'s fill, with its tip and priority fee.
+    fn fill_meta(&self, plan: &TradePlan) -> Result<FillMeta> {
+        let mut meta = FillMeta::from_plan(plan);
+        if self.cfg.include_tips {
+            let s = settings_for_plan(self.settings, plan);
+            let (bribe, fee) = match plan.side {
+                Side::Buy => (s.buy_bribe_sol, s.buy_priority_fee_sol),
+                Side::Sell => (s.sell_bribe_sol, s.sell_priority_fee_sol),
+            };
+            meta.tip_lamports = s.sol_to_lamports(bribe)?;
+            meta.fee_lamports = s.sol_to_lamports(fee)?;
+        }
+        Ok(meta)
+    }
+
+    fn fill(&mut self, ts: u64, plan: &TradePlan) -> Result<()> {
+        let market = self.markets.get(&plan.mint).copied().unwrap_or_default();
+        let fee_bps = pool_fee_bps(plan.dex);
+        let haircut = 1.0 - self.cfg.slippage_bps.min(10_000) as f64 / 10_000.0;
+        // reserves of another venue are stale (e.g. the curve after migration)
+        let reserves = market.reserves.filter(|(dex, _, _)| *dex == plan.dex);
+        let meta = self.fill_meta(plan)?;
+        let tip = meta.tip_lamports + meta.fee_lamports;
+        let owner = self.settings.owner();
+        let mut pm = self.shared.positions.lock().unwrap();
+
+        let (tokens, lamports) = match plan.side {
+            Side::Buy => {
+                let out = match (reserves, market.price) {
+                    (Some((_, tok, sol)), _) => quote_out(plan.buy_lamports, sol, tok, fee_bps),
+                    (None, Some(price)) if price > 0.0 => {
+                        (plan.buy_lamports as f64 * (10_000 - fee_bps) as f64 / 10_000.0 / price)
+                            as u64
+                    }
+                    _ => 0,
+                };
+                let tokens = (out as f64 * haircut) as u64;
+                if tokens == 0 {
+                    self.report.skipped += 1;
+                    return Ok(());
+                }
+                if let Some((dex, tok, sol)) = reserves {
+                    self.markets.get_mut(&plan.mint).unwrap().reserves =
+                        Some((dex, tok.saturating_sub(out), sol + plan.buy_lamports));
+                }
+                let cost = plan.buy_lamports + tip;
+                pm.record_buy(owner, plan.mint, plan.dex, tokens as u128, cost, &meta)?;
+                (tokens, cost)
+            }
+            Side::Sell => {
+                let held = pm.balance(owner, plan.mint) as u64;
+                let tokens = (held as f64 * plan.sell_pct.unwrap_or(0.0).clamp(0.0, 1.0)) as u64;
+                let gross = match (reserves, market.price) {
+                    _ if tokens == 0 => 0,
+                    (Some((_, tok, sol)), _) => quote_out(tokens, tok, sol, fee_bps),
+                    (None, Some(price)) => {
+                        (tokens as f64 * price * (10_000 - fee_bps) as f64 / 10_000.0) as u64
+                    }
+                    _ => 0,
+                };
+                if gross == 0 {
+                    self.report.skipped += 1;
+                    return Ok(());
+                }
+                if let Some((dex, tok, sol)) = reserves {
+                    self.markets.get_mut(&plan.mint).unwrap().reserves =
+                        Some((dex, tok + tokens, sol.saturating_sub(gross)));
+                }
+                let received = ((gross as f64 * haircut) as u64).saturating_sub(tip);
+                let cost_share = pm.position(owner, plan.mint).map_or(0, |p| {
+                    (p.cost_lamports as f64 * tokens as f64 / held as f64).round() as u64
+                });
+                self.realized_lamports += received as i64 - cost_share as i64;
+                pm.record_sell(owner, plan.mint, tokens as u128, received, &meta)?;
+                (tokens, received)
+            }
+        };
+
+        let unrealized_lamports = pm
+            .iter()
+            .filter(|p| p.balance > 0)
+            .map(|p| {
+                let value = p
+                    .last_price
+                    .map_or(p.cost_lamports as f64, |price| p.balance as f64 * price);
+                value as i64 - p.cost_lamports as i64
+            })
+            .sum();
+        let open_positions = pm.open_count();
+        drop(pm);
+
+        self.report.trades.push(BacktestTrade {
+            ts_ms: ts,
+            mint: plan.mint.to_string(),
+            side: plan.side,
+            strategy: plan.strategy.clone(),
+            wallet_label: plan.wallet_label.clone(),
+            tokens,
+            lamports,
+            price: lamports as f64 / tokens as f64,
+        });
+        self.report.pnl_curve.push(PnlPoint {
+            ts_ms: ts,
+            realized_lamports: self.realized_lamports,
+            unrealized_lamports,
+            open_positions,
+        });
+        Ok(())
+    }
+}
+
+impl BacktestReport {
+    /// Write `trades.jsonl`, `pnl_curve.csv` and `wallet_stats.json` to `dir`.
+    pub fn write_to(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+
+        let mut trades = fs::File::create(dir.join("trades.jsonl"))?;
+        for t in &self.trades {
+            writeln!(trades, "{}", serde_json::to_string(t)?)?;
+        }
+
+        let mut curve = fs::File::create(dir.join("pnl_curve.csv"))?;
+        writeln!(curve, "ts_ms,realized_sol,unrealized_sol,open_positions")?;
+        for p in &self.pnl_curve {
+            writeln!(
+                curve,
+                "{},{:.9},{:.9},{}",
+                p.ts_ms,
+                p.realized_lamports as f64 / 1e9,
+                p.unrealized_lamports as f64 / 1e9,
+                p.open_positions
+            )?;
+        }
+
+        let stats: HashMap<&String, &WalletStats> =
+            self.wallet_stats.iter().map(|(l, s)| (l, s)).collect();
+        fs::write(
+            dir.join("wallet_stats.json"),
+            serde_json::to_vec_pretty(&stats)?,
+        )?;
+        Ok(())
+    }
+
+    pub fn print_summary(&self) {
+        let last = self.pnl_curve.last();
+        println!(
+            "📊 [BACKTEST] {} trades, {} skipped",
+            self.trades.len(),
+            self.skipped
+        );
+        println!(
+            "📊 [BACKTEST] Realized {:.4} SOL, unrealized {:.4} SOL",
+            last.map_or(0, |p| p.realized_lamports) as f64 / 1e9,
+            last.map_or(0, |p| p.unrealized_lamports) as f64 / 1e9
+        );
+        for (label, s) in &self.wallet_stats {
+            println!(
+                "📊 [BACKTEST] {:<20} {:>3} trades, win rate {:>5.1}%, avg return {:>7.1}%, PnL {:.4} SOL",
+                label,
+                s.trades,
+                s.win_rate() * 100.0,
+                s.avg_return_pct(),
+                s.realized_pnl_lamports as f64 / 1e9
+            );
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Recorded event streams a backtest replays.
+//!
+//! The capture format is JSON lines, one [`ReplayEvent`] per line:
+//!
+//! ```json
+//! {"type":"fill","ts_ms":1718000000000,"mint":"…","side":"Buy","dex":"Pumpfun","wallet_label":"w1","cost_lamports":500000000,"pct_of_balance":0.0}
+//! {"type":"pool","ts_ms":1718000000400,"mint":"…","dex":"Pumpfun","token_reserve":1000000000000000,"sol_reserve":30000000000}
+//! {"type":"price","ts_ms":1718000001000,"mint":"…","price":0.031}
+//! ```
+//!
+//! `live_trades.jsonl` can be replayed too; it carries no wallet or venue,
+//! so those are supplied by the caller (see [`load_live_trades`]).
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    fs,
+    io::{BufRead, BufReader},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::strategy::{DexKind, ObservedFill, Side};
+use crate::utils::live_trades::LiveTrade;
+
+/// An [`ObservedFill`] as stored in a capture.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedFill {
+    pub mint: String,
+    pub side: Side,
+    pub dex: DexKind,
+    pub wallet_label: String,
+    pub cost_lamports: u64,
+    pub pct_of_balance: f64,
+    #[serde(default)]
+    pub slot: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trader_sol_balance: Option<u64>,
+}
+
+impl From<&ObservedFill> for RecordedFill {
+    fn from(f: &ObservedFill) -> Self {
+        Self {
+            mint: f.mint.to_string(),
+            side: f.side,
+            dex: f.dex,
+            wallet_label: f.wallet_label.clone(),
+            cost_lamports: f.cost_lamports,
+            pct_of_balance: f.pct_of_balance,
+            slot: f.slot,
+            trader_sol_balance: f.trader_sol_balance,
+        }
+    }
+}
+
+impl RecordedFill {
+    pub fn to_observed(&self) -> Result<ObservedFill> {
+        Ok(ObservedFill {
+            mint: parse_mint(&self.mint)?,
+            side: self.side,
+            cost_lamports: self.cost_lamports,
+            pct_of_balance: self.pct_of_balance,
+            dex: self.dex,
+            wallet_label: self.wallet_label.clone(),
+            slot: self.slot,
+            trader_sol_balance: self.trader_sol_balance,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReplayEvent {
+    /// A tracked wallet's trade
+    Fill {
+        ts_ms: u64,
+        #[serde(flatten)]
+        fill: RecordedFill,
+    },
+    /// Reserves of a mint's pool (virtual reserves for a bonding curve)
+    Pool {
+        ts_ms: u64,
+        mint: String,
+        dex: DexKind,
+        token_reserve: u64,
+        sol_reserve: u64,
+        #[serde(default)]
+        slot: u64,
+    },
+    /// A traded price in lamports per base unit, when reserves are unknown
+    Price {
+        ts_ms: u64,
+        mint: String,
+        price: f64,
+    },
+}
+
+impl ReplayEvent {
+    pub fn ts_ms(&self) -> u64 {
+        match self {
+            ReplayEvent::Fill { ts_ms, .. }
+            | ReplayEvent::Pool { ts_ms, .. }
+            | ReplayEvent::Price { ts_ms, .. } => *ts_ms,
+        }
+    }
+}
+
+pub fn parse_mint(mint: &str) -> Result<Pubkey> {
+    Pubkey::from_str(mint.trim()).map_err(|e| anyhow!("invalid mint `{}`: {}", mint, e))
+}
+
+fn read_lines<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Vec<T>> {
+    let file = fs::File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    let mut out = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        out.push(
+            serde_json::from_str(&line)
+                .with_context(|| format!("{} line {}", path.display(), i + 1))?,
+        );
+    }
+    Ok(out)
+}
+
+/// Load a capture file, ordered by time (stable for equal timestamps).
+pub fn load_capture(path: &Path) -> Result<Vec<ReplayEvent>> {
+    let mut events: Vec<ReplayEvent> = read_lines(path)?;
+    events.sort_by_key(|e| e.ts_ms());
+    Ok(events)
+}
+
+/// Load `live_trades.jsonl` as fills of `wallet_label` on `dex`, each
+/// preceded by its traded price. Sells count as full exits since the file
+/// doesn't record the trader's balance.
+pub fn load_live_trades(path: &Path, wallet_label: &str, dex: DexKind) -> Result<Vec<ReplayEvent>> {
+    let trades: Vec<LiveTrade> = read_lines(path)?;
+    let mut events = Vec::with_capacity(trades.len() * 2);
+    for t in trades {
+        let side = match t.side.as_str() {
+            "buy" => Side::Buy,
+            "sell" => Side::Sell,
+            _ => continue,
+        };
+        let ts_ms = t.timestamp.timestamp_millis().max(0) as u64;
+        let lamports = (t.sol_amount * 1_000_000_000.0).round() as u64;
+        if t.token_amount > 0 {
+            events.push(ReplayEvent::Price {
+                ts_ms,
+                mint: t.mint.clone(),
+                price: lamports as f64 / t.token_amount as f64,
+            });
+        }
+        events.push(ReplayEvent::Fill {
+            ts_ms,
+            fill: RecordedFill {
+                mint: t.mint,
+                side,
+                dex,
+                wallet_label: wallet_label.to_string(),
+                cost_lamports: lamports,
+                pct_of_balance: if side == Side::Sell { 1.0 } else { 0.0 },
+                slot: 0,
+                trader_sol_balance: None,
+            },
+        });
+    }
+    events.sort_by_key(|e| e.ts_ms());
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_round_trip() {
+        let line = r#"{"type":"fill","ts_ms":5,"mint":"So11111111111111111111111111111111111111112","side":"Buy","dex":"Pumpfun","wallet_label":"w1","cost_lamports":7,"pct_of_balance":0.0}"#;
+        let ev: ReplayEvent = serde_json::from_str(line).unwrap();
+        assert_eq!(ev.ts_ms(), 5);
+        match &ev {
+            ReplayEvent::Fill { fill, .. } => {
+                let f = fill.to_observed().unwrap();
+                assert_eq!(f.cost_lamports, 7);
+                assert_eq!(f.slot, 0);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        let back: ReplayEvent = serde_json::from_str(&serde_json::to_string(&ev).unwrap()).unwrap();
+        assert_eq!(back.ts_ms(), 5);
+    }
+}
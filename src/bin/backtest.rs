@@ -0,0 +1,85 @@
+//! Replay recorded fills through the strategy engine and report the PnL.
+//!
+//! Usage:
+//!   cargo run --bin backtest -- --events capture.jsonl
+//!   cargo run --bin backtest -- --events live_trades.jsonl --format live-trades --wallet-label w1 --dex Pumpfun
+
+use anyhow::{anyhow, Result};
+use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
+
+use copybot_ultimate_v2::backtest::{self, replay, BacktestConfig};
+use copybot_ultimate_v2::config::settings::Settings;
+use copybot_ultimate_v2::strategy::DexKind;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Format {
+    /// JSON lines of `ReplayEvent`s
+    Capture,
+    /// `live_trades.jsonl` as written by the bot
+    LiveTrades,
+}
+
+#[derive(Parser, Debug)]
+#[command(about = "Replay recorded fills through the strategy engine")]
+struct Args {
+    /// Event file to replay
+    #[arg(long)]
+    events: PathBuf,
+    #[arg(long, value_enum, default_value = "capture")]
+    format: Format,
+    /// Settings file (strategies, sizing, risk); defaults to the bot's
+    #[arg(long)]
+    settings: Option<PathBuf>,
+    /// Wallet the trades of a live-trades file are attributed to
+    #[arg(long, default_value = "replay")]
+    wallet_label: String,
+    /// Venue of a live-trades file (e.g. Pumpfun, PumpSwap)
+    #[arg(long, default_value = "Pumpfun")]
+    dex: String,
+    /// Delay between a plan and its fill
+    #[arg(long, default_value_t = 400)]
+    latency_ms: u64,
+    /// Extra adverse price move on every fill
+    #[arg(long, default_value_t = 100)]
+    slippage_bps: u64,
+    /// Don't charge bribes and priority fees
+    #[arg(long)]
+    no_tips: bool,
+    /// Output directory for trades.jsonl, pnl_curve.csv and wallet_stats.json
+    #[arg(long, default_value = "backtest_out")]
+    out: PathBuf,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let settings = match &args.settings {
+        Some(path) => Settings::load_from_file(path)?,
+        None => Settings::load()?,
+    };
+    let events = match args.format {
+        Format::Capture => replay::load_capture(&args.events)?,
+        Format::LiveTrades => {
+            let dex: DexKind = serde_json::from_value(serde_json::Value::String(args.dex.clone()))
+                .map_err(|_| anyhow!("unknown dex `{}`", args.dex))?;
+            replay::load_live_trades(&args.events, &args.wallet_label, dex)?
+        }
+    };
+    println!(
+        "⏪ [BACKTEST] Replaying {} events from {}",
+        events.len(),
+        args.events.display()
+    );
+
+    let cfg = BacktestConfig {
+        latency_ms: args.latency_ms,
+        slippage_bps: args.slippage_bps,
+        include_tips: !args.no_tips,
+    };
+    let report = backtest::run(&settings, &events, &cfg)?;
+    report.write_to(&args.out)?;
+    report.print_summary();
+    println!("💾 [BACKTEST] Report written to {}", args.out.display());
+    Ok(())
+}
@@ -0,0 +1,114 @@
+//! Record the Geyser stream to a file, or serve a recording back over gRPC.
+//!
+//! Usage:
+//!   cargo run --bin geyser_recorder -- record --out session.geyser
+//!   cargo run --bin geyser_recorder -- replay --file session.geyser --listen 127.0.0.1:10000 --speed 4
+//!
+//! Point `geyser_url` at the replay address to run the bot against it.
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use std::{net::SocketAddr, path::PathBuf, time::Duration};
+
+use copybot_ultimate_v2::config::settings::Settings;
+use copybot_ultimate_v2::rpc::{
+    geyser_filter,
+    geyser_recording::{self, RecordingWriter, ReplayServer},
+};
+
+#[derive(Parser, Debug)]
+#[command(about = "Record and replay the Geyser stream")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Record the tracked wallets' transactions from `geyser_url`
+    Record {
+        /// Recording to write
+        #[arg(long)]
+        out: PathBuf,
+        /// Settings file; defaults to the bot's
+        #[arg(long)]
+        settings: Option<PathBuf>,
+        /// Further accounts whose transactions to record
+        #[arg(long = "account")]
+        accounts: Vec<String>,
+        /// Accounts whose updates to record (e.g. pool vaults)
+        #[arg(long = "account-updates")]
+        account_updates: Vec<String>,
+        /// Stop after this many seconds instead of on Ctrl-C
+        #[arg(long)]
+        duration_secs: Option<u64>,
+    },
+    /// Serve a recording as a Geyser gRPC endpoint
+    Replay {
+        /// Recording to serve
+        #[arg(long)]
+        file: PathBuf,
+        #[arg(long, default_value = "127.0.0.1:10000")]
+        listen: SocketAddr,
+        /// Pace relative to the recording; 0 sends as fast as the client reads
+        #[arg(long, default_value_t = 1.0)]
+        speed: f64,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    match Args::parse().command {
+        Command::Record {
+            out,
+            settings,
+            accounts: extra,
+            account_updates,
+            duration_secs,
+        } => {
+            let settings = match &settings {
+                Some(path) => Settings::load_from_file(path)?,
+                None => Settings::load()?,
+            };
+            let mut accounts = geyser_filter::accounts(&settings);
+            accounts.extend(extra);
+            accounts.sort();
+            accounts.dedup();
+
+            let mut writer = RecordingWriter::create(&out)?;
+            let stop = async {
+                match duration_secs {
+                    Some(secs) => tokio::time::sleep(Duration::from_secs(secs)).await,
+                    None => {
+                        let _ = tokio::signal::ctrl_c().await;
+                    }
+                }
+            };
+            geyser_recording::record(
+                &settings.geyser_url,
+                settings.geyser_token.as_deref(),
+                accounts,
+                account_updates,
+                &mut writer,
+                stop,
+            )
+            .await?;
+            println!(
+                "💾 [GEYSER_RECORDING] Wrote {} updates to {}",
+                writer.count(),
+                out.display()
+            );
+        }
+        Command::Replay {
+            file,
+            listen,
+            speed,
+        } => {
+            let updates = geyser_recording::read_recording(&file)?;
+            ReplayServer::new(updates, Some(speed))
+                .serve(listen)
+                .await?;
+        }
+    }
+    Ok(())
+}
@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
     native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
     signature::{Keypair, Signer},
 };
 
@@ -23,6 +24,64 @@ pub struct WalletConfig {
     pub sol_gate: f64,
     /// Per-wallet buy amount - amount to buy when following this wallet
     pub buy_amount_sol: f64,
+    /// Weight of this wallet's buys in the `consensus_buy` score
+    #[serde(default = "default_consensus_weight")]
+    pub consensus_weight: f64,
+    /// Per-wallet overrides of the global buy/sell tuning
+    #[serde(flatten)]
+    pub profile: TradingProfile,
+}
+
+/// ------------------------------------------------------------------
+/// Per-wallet trading profile
+/// ------------------------------------------------------------------
+/// Every field overrides the global setting of the same name for trades
+/// triggered by that tracked wallet. Unset fields inherit the global value.
+/// Stored flat inside the wallet entry, e.g.
+/// `{ "label": "scalper", ..., "sell_bribe_sol": 0.004, "stop_loss_percent": -15 }`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TradingProfile {
+    /* -------- BUY tuning ---------------------------- */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buy_sizing: Option<BuySizing>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mint_age: Option<MintAgeConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale_in: Option<ScaleInConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buy_slippage_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buy_bribe_sol: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buy_priority_fee_sol: Option<f64>,
+
+    /* -------- SELL tuning --------------------------- */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sell_amount_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sell_min_sol_out: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sell_slippage_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sell_bribe_sol: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sell_priority_fee_sol: Option<f64>,
+
+    /* -------- exits --------------------------------- */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub take_profit_ladder: Option<Vec<TakeProfitRung>>,
+    /// Stop-loss threshold in PnL percent (e.g. -40.0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_loss_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_loss_sell_fraction: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trailing_stop_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trailing_stop_arm_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trailing_stop_sell_fraction: Option<f64>,
 }
 
 // Type alias for compatibility with API server
@@ -35,6 +94,65 @@ pub struct WalletKeypairEntry {
     pub private_key_base58: String,
 }
 
+/// ------------------------------------------------------------------
+/// Take-profit ladder
+/// ------------------------------------------------------------------
+/// One rung of a take-profit ladder: once unrealised PnL reaches
+/// `at_percent`, sell `sell_fraction` of the position as it was at entry.
+/// Fractions adding up to 1.0 (or more) close the position on the last rung.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TakeProfitRung {
+    pub at_percent: f64,
+    pub sell_fraction: f64,
+}
+
+/// Read a ladder from `value["take_profit_ladder"]`, sorted by threshold.
+/// `None` when the key is absent (or null); a malformed ladder is an error.
+fn parse_take_profit_ladder(value: &serde_json::Value) -> Result<Option<Vec<TakeProfitRung>>> {
+    let raw = match value.get("take_profit_ladder") {
+        Some(raw) if !raw.is_null() => raw,
+        _ => return Ok(None),
+    };
+    let mut ladder: Vec<TakeProfitRung> = serde_json::from_value(raw.clone())?;
+    ladder.sort_by(|a, b| a.at_percent.total_cmp(&b.at_percent));
+    Ok(Some(ladder))
+}
+
+/// ------------------------------------------------------------------
+/// Strategy registry entries
+/// ------------------------------------------------------------------
+/// One entry of the `strategies` section: which registered strategy to run,
+/// whether it is on, and its typed parameters. Order in the list is the
+/// order the engine runs them in.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StrategyConfig {
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub params: serde_json::Value,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_consensus_weight() -> f64 {
+    1.0
+}
+
+/// Strategies run when the settings file has no `strategies` section.
+pub fn default_strategies() -> Vec<StrategyConfig> {
+    ["follow_buy", "follow_sell", "take_profit", "stop_loss", "trailing_stop", "max_hold"]
+        .into_iter()
+        .map(|name| StrategyConfig {
+            name: name.to_string(),
+            enabled: true,
+            params: serde_json::Value::Null,
+        })
+        .collect()
+}
+
 /// ------------------------------------------------------------------
 /// Fresh Mint Cache Configuration
 /// ------------------------------------------------------------------
@@ -59,6 +177,170 @@ impl Default for FreshMintCacheConfig {
     }
 }
 
+/// ------------------------------------------------------------------
+/// Copy Buy Sizing
+/// ------------------------------------------------------------------
+/// How big a copy buy is relative to the tracked wallet's trade.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum SizingMode {
+    /// Always the wallet's `buy_amount_sol`
+    #[default]
+    Fixed,
+    /// `ratio` × the tracked wallet's spend
+    Ratio { ratio: f64 },
+    /// Buy `buy_sol` once the tracked spend reaches `min_spend_sol`; the
+    /// highest matching tier wins, spends below every tier are not copied
+    Tiers { tiers: Vec<SizingTier> },
+    /// `buy_amount_sol` × `multiplier` × the share of its SOL balance the
+    /// tracked wallet put in (fixed size when the balance is unknown)
+    BalanceShare { multiplier: f64 },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SizingTier {
+    pub min_spend_sol: f64,
+    pub buy_sol: f64,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct BuySizing {
+    #[serde(flatten)]
+    pub mode: SizingMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_buy_sol: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_buy_sol: Option<f64>,
+}
+
+/// ------------------------------------------------------------------
+/// Plan Merging
+/// ------------------------------------------------------------------
+/// How competing plans for the same mint and side from one fill (or tick)
+/// are reduced to one (see `strategy::merge`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergePolicy {
+    /// The strategy listed first in `strategies` wins, except that a stop
+    /// or urgent exit wins over any other sell
+    #[default]
+    Priority,
+    /// The biggest plan wins (largest sell fraction / buy amount)
+    Largest,
+    /// Sell fractions add up (capped at 100 %); buys fall back to `priority`
+    Sum,
+}
+
+/// ------------------------------------------------------------------
+/// Scale-In
+/// ------------------------------------------------------------------
+/// What to do when a tracked wallet buys a mint we already hold. Disabled
+/// means every such buy is copied like a first entry.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ScaleInConfig {
+    pub enabled: bool,
+    /// Adds allowed per position after the first entry
+    pub max_adds: u32,
+    /// Add *n* is sized at the normal copy size × `add_multiplier`^n
+    pub add_multiplier: f64,
+    /// Only add while the position is in profit
+    pub only_in_profit: bool,
+    /// Only add while the price is within this band around our average
+    /// cost, in PnL % (e.g. -20 / 50)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_pnl_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_pnl_percent: Option<f64>,
+}
+
+impl Default for ScaleInConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_adds: 2,
+            add_multiplier: 0.5,
+            only_in_profit: false,
+            min_pnl_percent: None,
+            max_pnl_percent: None,
+        }
+    }
+}
+
+/// ------------------------------------------------------------------
+/// Minimum Mint Age
+/// ------------------------------------------------------------------
+/// Skip copy buys of mints younger than this. Age comes from the mint's
+/// create transaction, or else the first slot / time the mint was seen in
+/// the stream (see `pool_tracker::record_mint_seen`).
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MintAgeConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_slots: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_seconds: Option<u64>,
+    /// Also skip mints whose create transaction we never saw and that we
+    /// first noticed too recently to tell their age
+    pub reject_unknown: bool,
+}
+
+/// ------------------------------------------------------------------
+/// Risk Limits
+/// ------------------------------------------------------------------
+/// Portfolio limits checked on every BUY plan before it is built (see
+/// `strategy::risk`). Unset limits are off; sells are never blocked.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RiskConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_open_positions: Option<usize>,
+    /// Cost basis of all open positions plus in-flight buys
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_total_exposure_sol: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_mint_exposure_sol: Option<f64>,
+    /// Stop buying for the rest of the UTC day once realized losses reach this
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub daily_loss_limit_sol: Option<f64>,
+    /// Minimum time between two buys triggered by the same tracked wallet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet_cooldown_seconds: Option<u64>,
+}
+
+/// ------------------------------------------------------------------
+/// Wallet Scoring Configuration
+/// ------------------------------------------------------------------
+/// Feedback loop from closed positions to the tracked wallets that opened
+/// them (see `positions::wallet_stats`).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WalletScoringConfig {
+    pub enabled: bool,
+    /// Closed trades a wallet needs before its score is applied
+    pub min_trades: u32,
+    /// Bounds of the multiplier applied to `buy_amount_sol`
+    pub min_size_multiplier: f64,
+    pub max_size_multiplier: f64,
+    /// Skip buys from wallets whose win rate (0‑1) falls below this
+    pub disable_below_win_rate: Option<f64>,
+    /// Skip buys from wallets whose average return % falls below this
+    pub disable_below_avg_return_pct: Option<f64>,
+}
+
+impl Default for WalletScoringConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_trades: 5,
+            min_size_multiplier: 0.5,
+            max_size_multiplier: 2.0,
+            disable_below_win_rate: None,
+            disable_below_avg_return_pct: None,
+        }
+    }
+}
+
 /// ------------------------------------------------------------------
 /// Serializable Settings for API responses
 /// ------------------------------------------------------------------
@@ -82,9 +364,27 @@ pub struct SerializableSettings {
     pub sell_slippage_percent: f64,
     pub sell_bribe_sol: f64,
     pub sell_priority_fee_sol: f64,
-    pub take_profit_percent: f64,
-    pub take_profit_sell_fraction: f64,
+    pub take_profit_ladder: Vec<TakeProfitRung>,
+    pub stop_loss_percent: Option<f64>,
+    pub stop_loss_sell_fraction: f64,
+    pub trailing_stop_percent: Option<f64>,
+    pub trailing_stop_arm_percent: f64,
+    pub trailing_stop_sell_fraction: f64,
+    pub strategy_tick_ms: u64,
+    pub max_hold_seconds: Option<u64>,
+    pub stale_position_seconds: Option<u64>,
+    pub strategies: Vec<StrategyConfig>,
     pub fresh_mint_cache: FreshMintCacheConfig,
+    pub wallet_scoring: WalletScoringConfig,
+    pub buy_sizing: BuySizing,
+    pub risk: RiskConfig,
+    pub filters_file: Option<String>,
+    pub mint_age: MintAgeConfig,
+    pub merge_policy: MergePolicy,
+    pub scale_in: ScaleInConfig,
+    pub emergency_sell_bribe_sol: Option<f64>,
+    pub emergency_sell_priority_fee_sol: Option<f64>,
+    pub paper_trading: bool,
 }
 
 /// ------------------------------------------------------------------
@@ -124,8 +424,44 @@ pub struct Settings {
 
     /* -------- shared objects ------------------------ */
     pub rpc_client: Arc<RpcClient>,
-    pub take_profit_percent: f64,
-    pub take_profit_sell_fraction: f64,
+    pub take_profit_ladder: Vec<TakeProfitRung>,
+    /// Global stop-loss threshold in PnL percent (negative); `None` disables it
+    pub stop_loss_percent: Option<f64>,
+    pub stop_loss_sell_fraction: f64,
+    /// Sell once price drops this many % below the position's peak; `None` disables it
+    pub trailing_stop_percent: Option<f64>,
+    /// Peak PnL % the position must reach before the trailing stop is armed
+    pub trailing_stop_arm_percent: f64,
+    pub trailing_stop_sell_fraction: f64,
+    /// Interval of the strategy timer that drives `Strategy::on_tick`
+    pub strategy_tick_ms: u64,
+    /// Exit positions held longer than this; `None` disables it
+    pub max_hold_seconds: Option<u64>,
+    /// Exit positions without any fill or price update for this long; `None` disables it
+    pub stale_position_seconds: Option<u64>,
+    /// Enabled strategies, their order and params (see `strategy::registry`)
+    pub strategies: Vec<StrategyConfig>,
+    /// Per-wallet performance scoring and buy-size weighting
+    pub wallet_scoring: WalletScoringConfig,
+    /// How copy buys are sized relative to the tracked trade
+    pub buy_sizing: BuySizing,
+    /// Portfolio limits applied to BUY plans
+    pub risk: RiskConfig,
+    /// Reloadable mint / creator / venue allow & deny lists (see
+    /// `strategy::filters`); relative paths are under `workdir`
+    pub filters_file: Option<String>,
+    /// Minimum age of a mint before it is copied
+    pub mint_age: MintAgeConfig,
+    /// How competing plans for one mint and side are merged
+    pub merge_policy: MergePolicy,
+    /// Adds to positions we already hold
+    pub scale_in: ScaleInConfig,
+    /// Bribe for emergency exits (creator sells, rugs); `None` uses `sell_bribe_sol`
+    pub emergency_sell_bribe_sol: Option<f64>,
+    /// Priority fee for emergency exits; `None` uses `sell_priority_fee_sol`
+    pub emergency_sell_priority_fee_sol: Option<f64>,
+    /// Build plans but simulate their fills instead of submitting (see `crate::paper`)
+    pub paper_trading: bool,
 }
 
 impl Settings {
@@ -170,9 +506,74 @@ impl Settings {
         let sell_slippage_percent = json["sell_slippage_percent"].as_f64().unwrap_or(0.5);
         let sell_bribe_sol = json["sell_bribe_sol"].as_f64().unwrap_or(0.0001);
         let sell_priority_fee_sol = json["sell_priority_fee_sol"].as_f64().unwrap_or(0.0001);
-        let take_profit_percent = json["take_profit_percent"].as_f64().unwrap_or(120.0);
-        let take_profit_sell_fraction = json["take_profit_sell_fraction"].as_f64().unwrap_or(0.5);
+        let take_profit_ladder = parse_take_profit_ladder(&json)
+            .context("parsing `take_profit_ladder`")?
+            .unwrap_or_else(|| {
+                // Legacy single-threshold keys become a one-rung ladder
+                vec![TakeProfitRung {
+                    at_percent: json["take_profit_percent"].as_f64().unwrap_or(120.0),
+                    sell_fraction: json["take_profit_sell_fraction"].as_f64().unwrap_or(0.5),
+                }]
+            });
+        let stop_loss_percent = json["stop_loss_percent"].as_f64();
+        let stop_loss_sell_fraction = json["stop_loss_sell_fraction"].as_f64().unwrap_or(1.0);
+        let trailing_stop_percent = json["trailing_stop_percent"].as_f64();
+        let trailing_stop_arm_percent = json["trailing_stop_arm_percent"].as_f64().unwrap_or(0.0);
+        let trailing_stop_sell_fraction = json["trailing_stop_sell_fraction"].as_f64().unwrap_or(1.0);
+
+        let strategy_tick_ms = json["strategy_tick_ms"].as_u64().unwrap_or(1000);
+        let max_hold_seconds = json["max_hold_seconds"].as_u64();
+        let stale_position_seconds = json["stale_position_seconds"].as_u64();
+        let strategies = match json.get("strategies") {
+            Some(list) => serde_json::from_value(list.clone()).context("parsing `strategies`")?,
+            None => default_strategies(),
+        };
+
+        /* -------- wallet scoring --------------------------------- */
+        let wallet_scoring = match json.get("wallet_scoring") {
+            Some(v) => serde_json::from_value(v.clone()).context("parsing `wallet_scoring`")?,
+            None => WalletScoringConfig::default(),
+        };
+
+        /* -------- copy buy sizing -------------------------------- */
+        let buy_sizing = match json.get("buy_sizing") {
+            Some(v) => serde_json::from_value(v.clone()).context("parsing `buy_sizing`")?,
+            None => BuySizing::default(),
+        };
+
+        /* -------- risk limits ------------------------------------ */
+        let risk = match json.get("risk") {
+            Some(v) => serde_json::from_value(v.clone()).context("parsing `risk`")?,
+            None => RiskConfig::default(),
+        };
+
+        /* -------- trade filters ---------------------------------- */
+        let filters_file = json["filters_file"].as_str().map(str::to_string);
 
+        /* -------- minimum mint age ------------------------------- */
+        let mint_age = match json.get("mint_age") {
+            Some(v) => serde_json::from_value(v.clone()).context("parsing `mint_age`")?,
+            None => MintAgeConfig::default(),
+        };
+
+        /* -------- plan merging ----------------------------------- */
+        let merge_policy = match json.get("merge_policy") {
+            Some(v) => serde_json::from_value(v.clone()).context("parsing `merge_policy`")?,
+            None => MergePolicy::default(),
+        };
+
+        /* -------- scale-in --------------------------------------- */
+        let scale_in = match json.get("scale_in") {
+            Some(v) => serde_json::from_value(v.clone()).context("parsing `scale_in`")?,
+            None => ScaleInConfig::default(),
+        };
+
+        /* -------- emergency exits -------------------------------- */
+        let emergency_sell_bribe_sol = json["emergency_sell_bribe_sol"].as_f64();
+        let emergency_sell_priority_fee_sol = json["emergency_sell_priority_fee_sol"].as_f64();
+
+        /* -------- paper trading ---------------------------------- */
+        let paper_trading = json["paper_trading"].as_bool().unwrap_or(false);
 
         /* -------- fresh mint cache configuration ----------------- */
         let fresh_mint_cache = if let Some(cache_config) = json.get("fresh_mint_cache") {
@@ -193,6 +594,12 @@ impl Settings {
                 // Per-wallet SOL gate and buy amount - required fields
                 let sol_gate = wallet_value["sol_gate"].as_f64().unwrap_or(0.001);
                 let buy_amount_sol = wallet_value["buy_amount_sol"].as_f64().unwrap_or(0.003);
+                let consensus_weight = wallet_value["consensus_weight"].as_f64().unwrap_or(1.0);
+                // Optional overrides of the global buy/sell tuning
+                let mut profile: TradingProfile = serde_json::from_value(wallet_value.clone())
+                    .with_context(|| format!("parsing trading profile of wallet `{label}`"))?;
+                profile.take_profit_ladder = parse_take_profit_ladder(wallet_value)
+                    .with_context(|| format!("parsing `take_profit_ladder` of wallet `{label}`"))?;
                 
                 tracked_wallets.push(WalletConfig {
                     label,
@@ -200,6 +607,8 @@ impl Settings {
                     enabled,
                     sol_gate,
                     buy_amount_sol,
+                    consensus_weight,
+                    profile,
                 });
             }
         }
@@ -248,8 +657,26 @@ impl Settings {
             sell_priority_fee_sol,
             fresh_mint_cache,
             rpc_client,
-            take_profit_percent,
-            take_profit_sell_fraction,
+            take_profit_ladder,
+            stop_loss_percent,
+            stop_loss_sell_fraction,
+            trailing_stop_percent,
+            trailing_stop_arm_percent,
+            trailing_stop_sell_fraction,
+            strategy_tick_ms,
+            max_hold_seconds,
+            stale_position_seconds,
+            strategies,
+            wallet_scoring,
+            buy_sizing,
+            risk,
+            filters_file,
+            mint_age,
+            merge_policy,
+            scale_in,
+            emergency_sell_bribe_sol,
+            emergency_sell_priority_fee_sol,
+            paper_trading,
         })
     }
 
@@ -296,8 +723,26 @@ impl Settings {
             "sell_slippage_percent": self.sell_slippage_percent,
             "sell_bribe_sol": self.sell_bribe_sol,
             "sell_priority_fee_sol": self.sell_priority_fee_sol,
-            "take_profit_percent": self.take_profit_percent,
-            "take_profit_sell_fraction": self.take_profit_sell_fraction
+            "take_profit_ladder": self.take_profit_ladder,
+            "stop_loss_percent": self.stop_loss_percent,
+            "stop_loss_sell_fraction": self.stop_loss_sell_fraction,
+            "trailing_stop_percent": self.trailing_stop_percent,
+            "trailing_stop_arm_percent": self.trailing_stop_arm_percent,
+            "trailing_stop_sell_fraction": self.trailing_stop_sell_fraction,
+            "strategy_tick_ms": self.strategy_tick_ms,
+            "max_hold_seconds": self.max_hold_seconds,
+            "stale_position_seconds": self.stale_position_seconds,
+            "wallet_scoring": self.wallet_scoring,
+            "buy_sizing": self.buy_sizing,
+            "risk": self.risk,
+            "filters_file": self.filters_file,
+            "mint_age": self.mint_age,
+            "merge_policy": self.merge_policy,
+            "scale_in": self.scale_in,
+            "emergency_sell_bribe_sol": self.emergency_sell_bribe_sol,
+            "emergency_sell_priority_fee_sol": self.emergency_sell_priority_fee_sol,
+            "paper_trading": self.paper_trading,
+            "strategies": self.strategies
         });
 
         let json_string = serde_json::to_string_pretty(&settings_json)?;
@@ -330,18 +775,90 @@ impl Settings {
             sell_slippage_percent: self.sell_slippage_percent,
             sell_bribe_sol: self.sell_bribe_sol,
             sell_priority_fee_sol: self.sell_priority_fee_sol,
-            take_profit_percent: self.take_profit_percent,
-            take_profit_sell_fraction: self.take_profit_sell_fraction,
+            take_profit_ladder: self.take_profit_ladder.clone(),
+            stop_loss_percent: self.stop_loss_percent,
+            stop_loss_sell_fraction: self.stop_loss_sell_fraction,
+            trailing_stop_percent: self.trailing_stop_percent,
+            trailing_stop_arm_percent: self.trailing_stop_arm_percent,
+            trailing_stop_sell_fraction: self.trailing_stop_sell_fraction,
+            strategy_tick_ms: self.strategy_tick_ms,
+            max_hold_seconds: self.max_hold_seconds,
+            stale_position_seconds: self.stale_position_seconds,
+            strategies: self.strategies.clone(),
             fresh_mint_cache: self.fresh_mint_cache.clone(),
+            wallet_scoring: self.wallet_scoring.clone(),
+            buy_sizing: self.buy_sizing.clone(),
+            risk: self.risk.clone(),
+            filters_file: self.filters_file.clone(),
+            mint_age: self.mint_age.clone(),
+            merge_policy: self.merge_policy,
+            scale_in: self.scale_in.clone(),
+            emergency_sell_bribe_sol: self.emergency_sell_bribe_sol,
+            emergency_sell_priority_fee_sol: self.emergency_sell_priority_fee_sol,
+            paper_trading: self.paper_trading,
         }
     }
 
+    /// --------------------------------------------------------------
+    /// Helper: the active trading keypair's address, i.e. the owner
+    /// of the positions this process opens.
+    /// --------------------------------------------------------------
+    pub fn owner(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+
     /// --------------------------------------------------------------
     /// Helper: get only enabled wallets for tracking.
     /// --------------------------------------------------------------
     pub fn enabled_wallets(&self) -> Vec<&WalletConfig> {
         self.tracked_wallets.iter().filter(|w| w.enabled).collect()
     }
+
+    /// --------------------------------------------------------------
+    /// Helper: look up a tracked wallet by its human-readable label.
+    /// --------------------------------------------------------------
+    pub fn wallet(&self, label: &str) -> Option<&WalletConfig> {
+        self.tracked_wallets.iter().find(|w| w.label == label)
+    }
+
+    /// --------------------------------------------------------------
+    /// Helper: trading profile overrides of a tracked wallet.
+    /// --------------------------------------------------------------
+    pub fn profile(&self, label: &str) -> Option<&TradingProfile> {
+        self.wallet(label).map(|w| &w.profile)
+    }
+
+    /// --------------------------------------------------------------
+    /// Effective settings for trades triggered by `label`: a copy of
+    /// these settings with the wallet's profile applied on top.
+    /// Unknown labels get the global settings unchanged.
+    /// --------------------------------------------------------------
+    pub fn for_wallet(&self, label: &str) -> Settings {
+        let mut s = self.clone();
+        let p = match self.profile(label) {
+            Some(p) => p,
+            None => return s,
+        };
+
+        if let Some(v) = &p.buy_sizing { s.buy_sizing = v.clone(); }
+        if let Some(v) = &p.mint_age { s.mint_age = v.clone(); }
+        if let Some(v) = &p.scale_in { s.scale_in = v.clone(); }
+        if let Some(v) = p.buy_slippage_percent { s.buy_slippage_percent = v; }
+        if let Some(v) = p.buy_bribe_sol { s.buy_bribe_sol = v; }
+        if let Some(v) = p.buy_priority_fee_sol { s.buy_priority_fee_sol = v; }
+        if let Some(v) = p.sell_amount_percent { s.sell_amount_percent = v; }
+        if let Some(v) = p.sell_min_sol_out { s.sell_min_sol_out = v; }
+        if let Some(v) = p.sell_slippage_percent { s.sell_slippage_percent = v; }
+        if let Some(v) = p.sell_bribe_sol { s.sell_bribe_sol = v; }
+        if let Some(v) = p.sell_priority_fee_sol { s.sell_priority_fee_sol = v; }
+        if let Some(v) = &p.take_profit_ladder { s.take_profit_ladder = v.clone(); }
+        if let Some(v) = p.stop_loss_percent { s.stop_loss_percent = Some(v); }
+        if let Some(v) = p.stop_loss_sell_fraction { s.stop_loss_sell_fraction = v; }
+        if let Some(v) = p.trailing_stop_percent { s.trailing_stop_percent = Some(v); }
+        if let Some(v) = p.trailing_stop_arm_percent { s.trailing_stop_arm_percent = v; }
+        if let Some(v) = p.trailing_stop_sell_fraction { s.trailing_stop_sell_fraction = v; }
+        s
+    }
 }
 
 /* ------------------------------------------------------------------ */
@@ -371,12 +888,58 @@ impl Clone for Settings {
             sell_priority_fee_sol: self.sell_priority_fee_sol,
             fresh_mint_cache: self.fresh_mint_cache.clone(),
             rpc_client: Arc::clone(&self.rpc_client),
-            take_profit_percent: self.take_profit_percent,
-            take_profit_sell_fraction: self.take_profit_sell_fraction,
+            take_profit_ladder: self.take_profit_ladder.clone(),
+            stop_loss_percent: self.stop_loss_percent,
+            stop_loss_sell_fraction: self.stop_loss_sell_fraction,
+            trailing_stop_percent: self.trailing_stop_percent,
+            trailing_stop_arm_percent: self.trailing_stop_arm_percent,
+            trailing_stop_sell_fraction: self.trailing_stop_sell_fraction,
+            strategy_tick_ms: self.strategy_tick_ms,
+            max_hold_seconds: self.max_hold_seconds,
+            stale_position_seconds: self.stale_position_seconds,
+            strategies: self.strategies.clone(),
+            wallet_scoring: self.wallet_scoring.clone(),
+            buy_sizing: self.buy_sizing.clone(),
+            risk: self.risk.clone(),
+            filters_file: self.filters_file.clone(),
+            mint_age: self.mint_age.clone(),
+            merge_policy: self.merge_policy,
+            scale_in: self.scale_in.clone(),
+            emergency_sell_bribe_sol: self.emergency_sell_bribe_sol,
+            emergency_sell_priority_fee_sol: self.emergency_sell_priority_fee_sol,
+            paper_trading: self.paper_trading,
         }
     }
 }
 
+/// Settings loaded from `json` with a throwaway trading wallet, for tests.
+#[cfg(test)]
+pub(crate) fn test_settings(mut json: serde_json::Value) -> Settings {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+    let dir = std::env::temp_dir().join(format!(
+        "settings_test_{}_{}",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    let keypair = Keypair::new();
+    let wallets = dir.join("wallets.json");
+    let entry = serde_json::json!([{
+        "name": "TradingBot",
+        "address": keypair.pubkey().to_string(),
+        "private_key_base58": keypair.to_base58_string(),
+    }]);
+    fs::write(&wallets, entry.to_string()).unwrap();
+
+    json["wallets_file"] = wallets.to_string_lossy().into();
+    json["workdir"] = dir.to_string_lossy().into();
+    let path = dir.join("settings.json");
+    fs::write(&path, json.to_string()).unwrap();
+    Settings::load_from_file(&path).unwrap()
+}
+
 impl fmt::Debug for Settings {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Settings")
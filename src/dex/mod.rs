@@ -15,12 +15,43 @@ pub mod types; // <--  NEW  (exports `PoolItem` etc.)
 
 use anyhow::{anyhow, Result};
 use solana_sdk::transaction::VersionedTransaction;
+use std::borrow::Cow;
 
 use crate::{
     config::settings::Settings,
     strategy::{DexKind, Side, TradePlan},
+    positions::holdings,
 };
 
+/// Settings to build `plan` with: the triggering wallet's trading profile
+/// applied on top of the globals, or the globals for unattributed plans.
+/// Urgent sells pay the emergency tip instead of the regular sell tip.
+pub fn settings_for_plan<'a>(settings: &'a Settings, plan: &TradePlan) -> Cow<'a, Settings> {
+    let mut out = match &plan.wallet_label {
+        Some(label) => Cow::Owned(settings.for_wallet(label)),
+        None => Cow::Borrowed(settings),
+    };
+    if plan.urgent && plan.side == Side::Sell {
+        if let Some(bribe) = settings.emergency_sell_bribe_sol {
+            out.to_mut().sell_bribe_sol = bribe;
+        }
+        if let Some(fee) = settings.emergency_sell_priority_fee_sol {
+            out.to_mut().sell_priority_fee_sol = fee;
+        }
+    }
+    out
+}
+
+/// Our balance of `plan.mint` for a sell: the plan's `known_token_amount`,
+/// else the holdings store's (kept by the position book, seeded from it on
+/// start-up and repaired by `positions::reconcile`).
+fn known_token_amount(settings: &Settings, plan: &TradePlan) -> Option<u64> {
+    match plan.known_token_amount {
+        Some(amount) => Some(amount),
+        None => holdings::amount(&settings.owner(), &plan.mint).filter(|amount| *amount > 0),
+    }
+}
+
 /// Convert a high‑level [`TradePlan`] into a signed [`VersionedTransaction`].
 /// For BUY operations, returns both the transaction and the calculated token amount.
 /// Includes intelligent fallback for zero-RPC assumptions.
@@ -28,6 +59,7 @@ pub async fn build_tx_from_plan(
     settings: &Settings,
     plan: &TradePlan,
 ) -> Result<(VersionedTransaction, Option<u64>)> {
+    let settings = &*settings_for_plan(settings, plan);
     match plan.dex {
         DexKind::Pumpfun => match plan.side {
             Side::Buy => {
@@ -84,7 +116,7 @@ pub async fn build_tx_from_plan(
                                 println!("🌙 [FALLBACK] Retrying as Moonshot for {}", plan.mint);
                                 let moonshot_dex = moonshot::MoonshotDex::new()?;
                                 
-                                let token_amount = if let Some(known_amount) = plan.known_token_amount {
+                                let token_amount = if let Some(known_amount) = known_token_amount(settings, plan) {
                                     (known_amount as f64 * pct) as u64
                                 } else {
                                     return Err(anyhow!("Moonshot SELL requires known_token_amount"));
@@ -120,7 +152,7 @@ pub async fn build_tx_from_plan(
                     }
                     
                     // Calculate token amount to sell
-                    let token_amount = if let Some(known_amount) = plan.known_token_amount {
+                    let token_amount = if let Some(known_amount) = known_token_amount(settings, plan) {
                         (known_amount as f64 * pct) as u64
                     } else {
                         return Err(anyhow!("Moonshot SELL requires known_token_amount"));
@@ -160,7 +192,7 @@ pub async fn build_tx_from_plan(
                         return Err(anyhow!("Sell percent must be > 0.0"));
                     }
                     
-                    let token_amount = if let Some(known_amount) = plan.known_token_amount {
+                    let token_amount = if let Some(known_amount) = known_token_amount(settings, plan) {
                         (known_amount as f64 * pct) as u64
                     } else {
                         return Err(anyhow!("Raydium Launchpad SELL requires known_token_amount"));
@@ -173,3 +205,33 @@ pub async fn build_tx_from_plan(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::settings::test_settings;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_urgent_sell_pays_emergency_tip_over_wallet_profile() {
+        let settings = test_settings(serde_json::json!({
+            "sell_bribe_sol": 0.001,
+            "sell_priority_fee_sol": 0.0001,
+            "emergency_sell_bribe_sol": 0.01,
+            "emergency_sell_priority_fee_sol": 0.001,
+            "tracked_wallets": [{
+                "label": "whale",
+                "address": Pubkey::new_unique().to_string(),
+                "sell_bribe_sol": 0.002
+            }]
+        }));
+        let mut plan = TradePlan::sell_percent(DexKind::PumpSwap, Pubkey::new_unique(), 1.0);
+        plan.wallet_label = Some("whale".to_string());
+        assert_eq!(settings_for_plan(&settings, &plan).sell_bribe_sol, 0.002);
+
+        plan.urgent = true;
+        let built = settings_for_plan(&settings, &plan);
+        assert_eq!(built.sell_bribe_sol, 0.01);
+        assert_eq!(built.sell_priority_fee_sol, 0.001);
+    }
+}
@@ -93,7 +93,9 @@ impl DexRouter {
         None
     }
     
-    /// Route transaction to the appropriate DEX based on detected DEX type
+    /// Route transaction to the appropriate DEX based on detected DEX type.
+    /// For a plan, pass `dex::settings_for_plan` output as `settings` so the
+    /// triggering wallet's profile and the emergency sell tip apply.
     pub async fn route_transaction(
         settings: &Settings,
         mint: &Pubkey,
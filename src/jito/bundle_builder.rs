@@ -42,6 +42,7 @@ pub fn get_tip_account() -> &'static str {
 
 /// Build a Jito bundle with main transaction + tip transaction
 /// Uses proper tip amounts from settings based on buy/sell side
+/// (pass `settings_for_plan` output so per-wallet tips apply)
 pub fn build_jito_bundle(
     main_tx: VersionedTransaction,
     settings: &Settings,
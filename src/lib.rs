@@ -1,7 +1,9 @@
 // App-specific modules
+pub mod backtest;
 pub mod config;
 pub mod dex;
 pub mod jito;
+pub mod paper;
 pub mod positions;
 pub mod rpc;
 pub mod state;
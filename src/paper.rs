@@ -0,0 +1,268 @@
+//! Paper trading: a dry-run execution mode.
+//!
+//! With `paper_trading` set, plans are still built by the DEX builders – so a
+//! plan that could not be built live fails here too – but the transaction is
+//! dropped instead of going to a `Submitter`. A simulated fill prices it
+//! against the latest known pool or bonding-curve reserves and books it in
+//! the paper `PositionManager` (`PositionManager::load_paper`), which keeps
+//! its own positions and wallet scoreboard. Strategies, risk limits and PnL
+//! then behave exactly as they would live.
+
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+use std::io;
+
+use crate::config::settings::Settings;
+use crate::dex::{self, router::DexRouter};
+use crate::positions::{ledger::FillMeta, PositionManager};
+use crate::strategy::engine::EngineShared;
+use crate::strategy::filters;
+use crate::strategy::{DexKind, Side, TradePlan};
+use crate::utils::pool_tracker;
+
+/// The position book for the configured mode: the paper book when
+/// `paper_trading` is set, the live one otherwise. Positions saved before
+/// owners were tracked are assigned to the active keypair. Only the live
+/// book reports closes to the auto-deny filter.
+pub fn position_manager(settings: &Settings) -> io::Result<PositionManager> {
+    let mut pm = if settings.paper_trading {
+        println!("📝 [PAPER] Paper trading enabled – nothing will be submitted");
+        PositionManager::load_paper(&settings.workdir)?
+    } else {
+        let mut pm = PositionManager::load(&settings.workdir)?;
+        // only live losses feed the auto-deny list
+        filters::use_workdir(&settings.workdir);
+        pm.on_close(filters::note_closed_trade);
+        pm
+    };
+    pm.claim_unowned(settings.owner())?;
+    Ok(pm)
+}
+
+/// Where a simulated fill got its price from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriceSource {
+    /// Constant-product quote against the latest pool reserves
+    Reserves,
+    /// The token amount the DEX builder computed for the buy
+    Builder,
+    /// The position's last known price
+    LastPrice,
+}
+
+impl fmt::Display for PriceSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PriceSource::Reserves => "pool reserves",
+            PriceSource::Builder => "builder estimate",
+            PriceSource::LastPrice => "last price",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A simulated fill, as booked in the paper positions.
+#[derive(Clone, Debug)]
+pub struct PaperFill {
+    pub mint: Pubkey,
+    pub side: Side,
+    /// Base units bought or sold
+    pub tokens: u64,
+    /// Lamports paid (buy, incl. tip) or received (sell, net of tip)
+    pub lamports: u64,
+    pub source: PriceSource,
+}
+
+/// Pool fee of `dex` in basis points, taken from the input side.
+pub(crate) fn pool_fee_bps(dex: DexKind) -> u64 {
+    match dex {
+        DexKind::Pumpfun => 100,
+        DexKind::Raydium | DexKind::RaydiumLaunchpad => 25,
+        _ => 30,
+    }
+}
+
+/// Constant-product output for `amount_in` after the pool fee.
+pub(crate) fn quote_out(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_bps: u64) -> u64 {
+    let amount_in = amount_in as u128 * (10_000 - fee_bps.min(10_000)) as u128 / 10_000;
+    let denom = reserve_in as u128 + amount_in;
+    if denom == 0 {
+        return 0;
+    }
+    (reserve_out as u128 * amount_in / denom) as u64
+}
+
+/// Build `plan` with the builder live trading would use and drop the
+/// transaction. Returns the builder's token estimate for buys, if any.
+async fn build(settings: &Settings, plan: &TradePlan, sell_tokens: u64) -> Result<Option<u64>> {
+    match plan.dex {
+        // migrated venues go through the router, as live
+        DexKind::PumpSwap | DexKind::Raydium | DexKind::Meteora => {
+            let amount = match plan.side {
+                Side::Buy => plan.buy_lamports,
+                Side::Sell => sell_tokens,
+            };
+            let (_tx, tokens) = DexRouter::route_transaction(
+                &dex::settings_for_plan(settings, plan),
+                &plan.mint,
+                plan.side,
+                amount,
+                Some(plan.dex),
+            )
+            .await?;
+            Ok((plan.side == Side::Buy && tokens > 0).then_some(tokens))
+        }
+        _ => {
+            let (_tx, tokens) = dex::build_tx_from_plan(settings, plan).await?;
+            Ok(tokens)
+        }
+    }
+}
+
+/// Build `plan`, simulate its fill and book it in `shared`'s positions.
+pub async fn execute(
+    settings: &Settings,
+    plan: &TradePlan,
+    shared: &EngineShared,
+) -> Result<PaperFill> {
+    let plan_settings = dex::settings_for_plan(settings, plan);
+    let mint = plan.mint;
+    let owner = settings.owner();
+    let fee_bps = pool_fee_bps(plan.dex);
+    // reserves of another venue are stale (e.g. the curve after migration)
+    let reserves = pool_tracker::latest_reserves(&mint).filter(|r| r.dex == plan.dex);
+
+    let (held, last_price) = {
+        let pm = shared.positions.lock().unwrap();
+        let pos = pm.position(owner, mint);
+        (
+            pos.map_or(0, |p| p.balance) as u64,
+            pos.and_then(|p| p.last_price),
+        )
+    };
+
+    match plan.side {
+        Side::Buy => {
+            let meta = FillMeta {
+                tip_lamports: plan_settings.sol_to_lamports(plan_settings.buy_bribe_sol)?,
+                fee_lamports: plan_settings.sol_to_lamports(plan_settings.buy_priority_fee_sol)?,
+                ..FillMeta::from_plan(plan)
+            };
+            let tip = meta.tip_lamports + meta.fee_lamports;
+            let estimate = build(settings, plan, 0).await?;
+
+            let (tokens, source) = if let Some(r) = reserves {
+                let out = quote_out(plan.buy_lamports, r.sol_reserve, r.token_reserve, fee_bps);
+                (out, PriceSource::Reserves)
+            } else if let Some(tokens) = estimate {
+                (tokens, PriceSource::Builder)
+            } else if let Some(price) = last_price.filter(|p| *p > 0.0) {
+                let net = plan.buy_lamports as f64 * (10_000 - fee_bps) as f64 / 10_000.0;
+                ((net / price) as u64, PriceSource::LastPrice)
+            } else {
+                return Err(anyhow!("no price known for {}", mint));
+            };
+            if tokens == 0 {
+                return Err(anyhow!("simulated buy of {} returned no tokens", mint));
+            }
+
+            let cost = plan.buy_lamports + tip;
+            let mut pm = shared.positions.lock().unwrap();
+            pm.record_buy(owner, mint, plan.dex, tokens as u128, cost, &meta)?;
+            pm.update_price(mint, plan.buy_lamports as f64 / tokens as f64)?;
+            drop(pm);
+
+            println!(
+                "📝 [PAPER] BUY {} – {:.4} SOL (+{:.4} tip) → {} tokens ({})",
+                mint,
+                plan.buy_lamports as f64 / 1e9,
+                tip as f64 / 1e9,
+                tokens,
+                source
+            );
+            Ok(PaperFill {
+                mint,
+                side: Side::Buy,
+                tokens,
+                lamports: cost,
+                source,
+            })
+        }
+        Side::Sell => {
+            let pct = plan
+                .sell_pct
+                .filter(|p| *p > 0.0)
+                .ok_or_else(|| anyhow!("TradePlan for SELL is missing `sell_pct`"))?;
+            if held == 0 {
+                return Err(anyhow!("no paper balance of {}", mint));
+            }
+            let tokens = ((held as f64 * pct.min(1.0)) as u64).max(1);
+
+            // builders read our balance from `known_token_amount` instead of the ATA
+            let mut plan = plan.clone();
+            plan.known_token_amount = Some(held);
+            build(settings, &plan, tokens).await?;
+
+            let meta = FillMeta {
+                tip_lamports: plan_settings.sol_to_lamports(plan_settings.sell_bribe_sol)?,
+                fee_lamports: plan_settings.sol_to_lamports(plan_settings.sell_priority_fee_sol)?,
+                ..FillMeta::from_plan(&plan)
+            };
+            let tip = meta.tip_lamports + meta.fee_lamports;
+            let (gross, source) = if let Some(r) = reserves {
+                (
+                    quote_out(tokens, r.token_reserve, r.sol_reserve, fee_bps),
+                    PriceSource::Reserves,
+                )
+            } else if let Some(price) = last_price.filter(|p| *p > 0.0) {
+                let gross = tokens as f64 * price * (10_000 - fee_bps) as f64 / 10_000.0;
+                (gross as u64, PriceSource::LastPrice)
+            } else {
+                return Err(anyhow!("no price known for {}", mint));
+            };
+            let received = gross.saturating_sub(tip);
+
+            let mut pm = shared.positions.lock().unwrap();
+            if gross > 0 {
+                pm.update_price(mint, gross as f64 / tokens as f64)?;
+            }
+            pm.record_sell(owner, mint, tokens as u128, received, &meta)?;
+            drop(pm);
+
+            println!(
+                "📝 [PAPER] SELL {} – {} tokens ({:.0}%) → {:.4} SOL (-{:.4} tip) ({})",
+                mint,
+                tokens,
+                pct * 100.0,
+                received as f64 / 1e9,
+                tip as f64 / 1e9,
+                source
+            );
+            Ok(PaperFill {
+                mint,
+                side: Side::Sell,
+                tokens,
+                lamports: received,
+                source,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_out() {
+        // 1 SOL into 30 SOL / 1B tokens, no fee
+        assert_eq!(
+            quote_out(1_000_000_000, 30_000_000_000, 1_000_000_000, 0),
+            32_258_064
+        );
+        // the fee comes off the input
+        assert!(quote_out(1_000_000_000, 30_000_000_000, 1_000_000_000, 100) < 32_258_064);
+        assert_eq!(quote_out(0, 0, 0, 0), 0);
+    }
+}
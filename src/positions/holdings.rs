@@ -0,0 +1,178 @@
+//! The one store of what each trading wallet holds.
+//!
+//! Every holding is keyed by (wallet, mint) and is either *pending* – a buy
+//! was submitted but has not landed – or *confirmed*: Geyser saw the buy,
+//! a fill was booked in a `PositionManager` or reconciliation found the
+//! tokens on chain. Confirmed holdings carry the token amount once known.
+//!
+//! `PositionManager` publishes every booked fill here. `token_tracker`,
+//! `dedupe` and `multi_wallet` are thin views over the store, so sell
+//! sizing, follow‑sell gating and the double‑buy guard all agree.
+
+use once_cell::sync::Lazy;
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::HashMap,
+    sync::RwLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Holdings by (wallet, mint)
+static HOLDINGS: Lazy<RwLock<HashMap<(Pubkey, Pubkey), Holding>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Holding {
+    /// The buy landed (or the tokens were found on chain)
+    pub confirmed: bool,
+    /// Base‑unit tokens, when known
+    pub amount: Option<u64>,
+    /// Unix ms of a submitted buy that has not landed yet
+    pub pending_since_ms: Option<u64>,
+}
+
+impl Holding {
+    /// Held as far as we know: confirmed and not known to be empty.
+    pub fn is_held(&self) -> bool {
+        self.confirmed && self.amount != Some(0)
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.confirmed && self.amount.is_none() && self.pending_since_ms.is_none()
+    }
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Apply `f` to the holding of (`wallet`, `mint`), dropping it once empty.
+fn update(wallet: &Pubkey, mint: &Pubkey, f: impl FnOnce(&mut Holding)) {
+    let mut holdings = HOLDINGS.write().unwrap();
+    let key = (*wallet, *mint);
+    let holding = holdings.entry(key).or_default();
+    f(holding);
+    if holding.is_empty() {
+        holdings.remove(&key);
+    }
+}
+
+/* --------------------------------------------------------------------- */
+/*  Writes                                                               */
+/* --------------------------------------------------------------------- */
+/// A buy was submitted.
+pub fn mark_pending(wallet: &Pubkey, mint: &Pubkey) {
+    update(wallet, mint, |h| h.pending_since_ms = Some(now_ms()));
+}
+
+/// A submitted buy failed.
+pub fn rollback_pending(wallet: &Pubkey, mint: &Pubkey) {
+    update(wallet, mint, |h| h.pending_since_ms = None);
+}
+
+/// The buy landed; `amount` is our balance afterwards, if known.
+pub fn confirm(wallet: &Pubkey, mint: &Pubkey, amount: Option<u64>) {
+    update(wallet, mint, |h| {
+        h.pending_since_ms = None;
+        h.confirmed = true;
+        if amount.is_some() {
+            h.amount = amount;
+        }
+    });
+}
+
+/// Our balance changed to `amount`, without saying whether a buy landed
+/// (e.g. a builder's estimate). A zero amount ends a confirmed holding.
+pub fn set_amount(wallet: &Pubkey, mint: &Pubkey, amount: u64) {
+    if amount == 0 {
+        clear(wallet, mint);
+    } else {
+        update(wallet, mint, |h| h.amount = Some(amount));
+    }
+}
+
+/// Sold in full (or gone from chain).
+pub fn clear(wallet: &Pubkey, mint: &Pubkey) {
+    HOLDINGS.write().unwrap().remove(&(*wallet, *mint));
+}
+
+/// Forget pending buys submitted more than `timeout_ms` ago.
+pub fn expire_pending(timeout_ms: u64) {
+    let now = now_ms();
+    let mut holdings = HOLDINGS.write().unwrap();
+    for holding in holdings.values_mut() {
+        if holding
+            .pending_since_ms
+            .map_or(false, |since| now.saturating_sub(since) > timeout_ms)
+        {
+            holding.pending_since_ms = None;
+        }
+    }
+    holdings.retain(|_, h| !h.is_empty());
+}
+
+/* --------------------------------------------------------------------- */
+/*  Reads                                                                */
+/* --------------------------------------------------------------------- */
+pub fn get(wallet: &Pubkey, mint: &Pubkey) -> Option<Holding> {
+    HOLDINGS.read().unwrap().get(&(*wallet, *mint)).copied()
+}
+
+/// Known token amount of (`wallet`, `mint`).
+pub fn amount(wallet: &Pubkey, mint: &Pubkey) -> Option<u64> {
+    get(wallet, mint).and_then(|h| h.amount)
+}
+
+pub fn is_held(wallet: &Pubkey, mint: &Pubkey) -> bool {
+    get(wallet, mint).map_or(false, |h| h.is_held())
+}
+
+/// All holdings of `wallet`, pending ones included, by mint.
+pub fn for_wallet(wallet: &Pubkey) -> HashMap<Pubkey, Holding> {
+    HOLDINGS
+        .read()
+        .unwrap()
+        .iter()
+        .filter(|((w, _), _)| w == wallet)
+        .map(|((_, mint), h)| (*mint, *h))
+        .collect()
+}
+
+/// Every holding.
+pub fn all() -> Vec<(Pubkey, Pubkey, Holding)> {
+    HOLDINGS
+        .read()
+        .unwrap()
+        .iter()
+        .map(|((wallet, mint), h)| (*wallet, *mint, *h))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_then_confirmed() {
+        let (wallet, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        mark_pending(&wallet, &mint);
+        assert!(!is_held(&wallet, &mint));
+        assert!(get(&wallet, &mint).unwrap().pending_since_ms.is_some());
+
+        confirm(&wallet, &mint, None);
+        assert!(is_held(&wallet, &mint));
+        assert_eq!(amount(&wallet, &mint), None);
+
+        set_amount(&wallet, &mint, 500);
+        assert_eq!(amount(&wallet, &mint), Some(500));
+        set_amount(&wallet, &mint, 0);
+        assert_eq!(get(&wallet, &mint), None);
+
+        mark_pending(&wallet, &mint);
+        rollback_pending(&wallet, &mint);
+        assert_eq!(get(&wallet, &mint), None);
+    }
+}
@@ -0,0 +1,341 @@
+//! Append‑only trade ledger.
+//!
+//! Every fill booked in the `PositionManager` is appended here as one JSON
+//! line and never rewritten, so closed positions keep a full record of how
+//! they did. Realized PnL per position, mint, tracked wallet and UTC day is
+//! derived from the ledger rather than kept as separate counters.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::strategy::{DexKind, Side, TradePlan};
+
+/* --------------------------------------------------------------------- */
+/*  On‑disk location                                                     */
+/* --------------------------------------------------------------------- */
+pub(crate) const STORAGE_FILE: &str = "ledger.jsonl";
+/// Ledger of the paper-trading positions
+pub(crate) const PAPER_STORAGE_FILE: &str = "paper_ledger.jsonl";
+
+/* --------------------------------------------------------------------- */
+/*  Fill details                                                         */
+/* --------------------------------------------------------------------- */
+/// What the caller knows about a fill beyond quantity and lamports.
+#[derive(Debug, Clone, Default)]
+pub struct FillMeta {
+    /// Priority and network fees, included in the fill's lamports
+    pub fee_lamports: u64,
+    /// Bribe / Jito tip, included in the fill's lamports
+    pub tip_lamports: u64,
+    pub signature: Option<String>,
+    pub slot: Option<u64>,
+    /// Strategy that produced the plan
+    pub strategy: Option<String>,
+    /// Tracked wallet behind the plan
+    pub wallet_label: Option<String>,
+    /// Take-profit rungs the sell fires, marked once it is booked
+    pub take_profit_rungs: Vec<usize>,
+    /// Share of the position a follow-sell mirrored from `wallet_label`
+    pub follow_sell_pct: Option<f64>,
+}
+
+impl FillMeta {
+    /// Strategy and wallet of `plan`; fees, tips and landing info are
+    /// filled in by the caller.
+    pub fn from_plan(plan: &TradePlan) -> Self {
+        Self {
+            strategy: plan.strategy.clone(),
+            wallet_label: plan.wallet_label.clone(),
+            take_profit_rungs: plan.take_profit_rungs.clone(),
+            follow_sell_pct: plan.follow_sell_pct,
+            ..Self::default()
+        }
+    }
+
+    /// Whether `name` produced the plan, alone or in a summed merge (`a+b`).
+    pub fn from_strategy(&self, name: &str) -> bool {
+        self.strategy
+            .as_deref()
+            .map_or(false, |s| s.split('+').any(|n| n == name))
+    }
+}
+
+/* --------------------------------------------------------------------- */
+/*  One ledger line                                                      */
+/* --------------------------------------------------------------------- */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// Unix seconds
+    pub ts: u64,
+    pub side: Side,
+    /// Trading wallet that filled
+    #[serde(default)]
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dex: Option<DexKind>,
+    /// Base‑unit tokens bought or sold
+    pub tokens: u128,
+    /// Paid (buy, incl. fees and tip) or received (sell, net of them)
+    pub lamports: u64,
+    pub fee_lamports: u64,
+    pub tip_lamports: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet_label: Option<String>,
+    /// `opened_at` of the position the fill belongs to; with `mint` it
+    /// identifies one round trip
+    pub position_opened_at: u64,
+    /// Tracked wallet credited with the position (its `opened_by`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opened_by: Option<String>,
+    /// SELL only – proceeds minus the cost basis sold; `None` if we held no
+    /// position to sell from, or only an adopted one without a cost basis
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub realized_lamports: Option<i64>,
+    /// SELL only – the fill closed the position
+    #[serde(default)]
+    pub closed: bool,
+}
+
+/// Realized result of one round trip.
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionPnl {
+    /// Trading wallet that held the position
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub opened_at: u64,
+    /// Time of the closing sell, `None` while open
+    pub closed_at: Option<u64>,
+    pub opened_by: Option<String>,
+    pub invested_lamports: u64,
+    pub realized_lamports: i64,
+}
+
+/* --------------------------------------------------------------------- */
+/*  Ledger                                                               */
+/* --------------------------------------------------------------------- */
+#[derive(Debug, Default)]
+pub struct Ledger {
+    entries: Vec<LedgerEntry>,
+    /// `None` keeps it in memory only (backtests)
+    path: Option<PathBuf>,
+}
+
+impl Ledger {
+    /// Load the ledger at `path` (and append to it from then on). A line
+    /// that can't be parsed – e.g. one torn by a crash mid-append – is
+    /// skipped with a warning.
+    pub fn load_from(path: &Path) -> io::Result<Self> {
+        let mut entries = Vec::new();
+        if path.exists() {
+            let raw = fs::read_to_string(path)?;
+            // end a torn last line so the next append starts on its own
+            if !raw.is_empty() && !raw.ends_with('\n') {
+                fs::OpenOptions::new()
+                    .append(true)
+                    .open(path)?
+                    .write_all(b"\n")?;
+            }
+            for (i, line) in raw.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str(line) {
+                    Ok(entry) => entries.push(entry),
+                    Err(e) => println!(
+                        "⚠️ [LEDGER] Skipping line {} of {}: {}",
+                        i + 1,
+                        path.display(),
+                        e
+                    ),
+                }
+            }
+        }
+        Ok(Self {
+            entries,
+            path: Some(path.to_path_buf()),
+        })
+    }
+
+    pub fn append(&mut self, entry: LedgerEntry) -> io::Result<()> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut line = serde_json::to_vec(&entry)?;
+            line.push(b'\n');
+            fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?
+                .write_all(&line)?;
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[LedgerEntry] {
+        &self.entries
+    }
+
+    fn sells(&self) -> impl Iterator<Item = (&LedgerEntry, i64)> {
+        self.entries
+            .iter()
+            .filter_map(|e| e.realized_lamports.map(|r| (e, r)))
+    }
+
+    /// Round trips in the order they were opened, open ones included, one
+    /// per (owner, mint, opening time).
+    pub fn realized_by_position(&self) -> Vec<PositionPnl> {
+        let mut out: Vec<PositionPnl> = Vec::new();
+        let mut index: HashMap<(Pubkey, Pubkey, u64), usize> = HashMap::new();
+        for e in &self.entries {
+            let i = *index
+                .entry((e.owner, e.mint, e.position_opened_at))
+                .or_insert_with(|| {
+                    out.push(PositionPnl {
+                        owner: e.owner,
+                        mint: e.mint,
+                        opened_at: e.position_opened_at,
+                        closed_at: None,
+                        opened_by: e.opened_by.clone(),
+                        invested_lamports: 0,
+                        realized_lamports: 0,
+                    });
+                    out.len() - 1
+                });
+            let pos = &mut out[i];
+            match e.side {
+                Side::Buy => pos.invested_lamports += e.lamports,
+                Side::Sell => {
+                    pos.realized_lamports += e.realized_lamports.unwrap_or(0);
+                    if e.closed {
+                        pos.closed_at = Some(e.ts);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    pub fn realized_by_mint(&self) -> HashMap<Pubkey, i64> {
+        let mut out = HashMap::new();
+        for (e, realized) in self.sells() {
+            *out.entry(e.mint).or_insert(0) += realized;
+        }
+        out
+    }
+
+    /// Keyed by the wallet credited with each position (`opened_by`), as in
+    /// the wallet scoreboard.
+    pub fn realized_by_wallet(&self) -> HashMap<String, i64> {
+        let mut out = HashMap::new();
+        for (e, realized) in self.sells() {
+            if let Some(label) = &e.opened_by {
+                *out.entry(label.clone()).or_insert(0) += realized;
+            }
+        }
+        out
+    }
+
+    /// UTC day number (Unix seconds / 86 400) → realized PnL of that day.
+    pub fn realized_by_day(&self) -> BTreeMap<u64, i64> {
+        let mut out = BTreeMap::new();
+        for (e, realized) in self.sells() {
+            *out.entry(e.ts / 86_400).or_insert(0) += realized;
+        }
+        out
+    }
+
+    /// Realized PnL of sells on UTC day `day`.
+    pub fn realized_on_day(&self, day: u64) -> i64 {
+        // newest entries last; stop once we're past the day
+        self.entries
+            .iter()
+            .rev()
+            .take_while(|e| e.ts / 86_400 >= day)
+            .filter(|e| e.ts / 86_400 == day)
+            .filter_map(|e| e.realized_lamports)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        ts: u64,
+        side: Side,
+        mint: Pubkey,
+        lamports: u64,
+        realized: Option<i64>,
+    ) -> LedgerEntry {
+        LedgerEntry {
+            ts,
+            side,
+            owner: Pubkey::default(),
+            mint,
+            dex: None,
+            tokens: 1,
+            lamports,
+            fee_lamports: 0,
+            tip_lamports: 0,
+            signature: None,
+            slot: None,
+            strategy: None,
+            wallet_label: None,
+            position_opened_at: 10,
+            opened_by: Some("w1".into()),
+            realized_lamports: realized,
+            closed: side == Side::Sell && realized == Some(-40),
+        }
+    }
+
+    #[test]
+    fn test_realized_rollups() {
+        let mint = Pubkey::new_unique();
+        let mut ledger = Ledger::default();
+        ledger
+            .append(entry(10, Side::Buy, mint, 100, None))
+            .unwrap();
+        ledger
+            .append(entry(20, Side::Sell, mint, 80, Some(30)))
+            .unwrap();
+        ledger
+            .append(entry(86_410, Side::Sell, mint, 10, Some(-40)))
+            .unwrap();
+
+        assert_eq!(ledger.realized_by_mint()[&mint], -10);
+        assert_eq!(ledger.realized_by_wallet()["w1"], -10);
+        assert_eq!(ledger.realized_on_day(0), 30);
+        assert_eq!(ledger.realized_on_day(1), -40);
+        let positions = ledger.realized_by_position();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].invested_lamports, 100);
+        assert_eq!(positions[0].closed_at, Some(86_410));
+
+        // another wallet's position in the same mint, opened the same second
+        let other = LedgerEntry {
+            owner: Pubkey::new_unique(),
+            ..entry(10, Side::Buy, mint, 50, None)
+        };
+        ledger.append(other).unwrap();
+        let positions = ledger.realized_by_position();
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[0].invested_lamports, 100);
+        assert_eq!(positions[1].invested_lamports, 50);
+    }
+}
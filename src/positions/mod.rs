@@ -3,29 +3,128 @@
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use std::ops::Sub;
+
+use crate::strategy::{DexKind, Side};
+
+pub mod holdings;
+pub mod ledger;
+pub mod reconcile;
+pub mod wallet_stats;
+use ledger::{FillMeta, Ledger, LedgerEntry};
+use wallet_stats::WalletScoreboard;
 use std::{
     collections::HashMap,
     fs,
     io::{self, Write},
-    path::Path,
-    time::{SystemTime, UNIX_EPOCH},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 /* --------------------------------------------------------------------- */
 /*  On‑disk location                                                     */
 /* --------------------------------------------------------------------- */
-const STORAGE_PATH: &str = "src/positions/positions.json";
+/// Directory under `Settings::workdir` holding the books
+const STORAGE_DIR: &str = "positions";
+/// Where the books were kept before they moved under the workdir
+const LEGACY_DIR: &str = "src/positions";
+const STORAGE_FILE: &str = "positions.json";
+/// Positions of paper trading (see `crate::paper`), kept apart from live ones
+const PAPER_STORAGE_FILE: &str = "paper_positions.json";
+
+/// Price updates are written at most this often; fills are written at once.
+const PRICE_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Path of `file` in the books directory under `workdir`. A book still at
+/// its old place in the source tree is copied over first.
+fn storage_path(workdir: &Path, file: &str) -> io::Result<PathBuf> {
+    state_path(workdir, STORAGE_DIR, LEGACY_DIR, file)
+}
+
+/// Path of `file` in `dir` under `workdir`, for state the bot manages. A
+/// file still in `legacy_dir` of the source tree is copied over first.
+pub(crate) fn state_path(
+    workdir: &Path,
+    dir: &str,
+    legacy_dir: &str,
+    file: &str,
+) -> io::Result<PathBuf> {
+    let path = workdir.join(dir).join(file);
+    let legacy = Path::new(legacy_dir).join(file);
+    if !path.exists() && legacy.exists() && legacy != path {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&legacy, &path)?;
+        println!(
+            "📦 [STORAGE] Copied {} to {}",
+            legacy.display(),
+            path.display()
+        );
+    }
+    Ok(path)
+}
+
+/// Replace `path` with `bytes` through a temp file in the same directory and
+/// a rename, so a crash leaves the old file or the new one, never a torn one.
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)
+}
 
 /* --------------------------------------------------------------------- */
 /*  A single open position                                               */
 /* --------------------------------------------------------------------- */
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Position {
+    /// Trading wallet holding the tokens (`Pubkey::default()` for positions
+    /// persisted before owners were tracked, until claimed)
+    #[serde(default)]
+    pub owner: Pubkey,
     pub mint: Pubkey,
     pub balance: u128,      // base‑unit tokens
     pub cost_lamports: u64, // total cost basis
+    /// Venue of the last buy, so timer‑driven exits know where to sell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dex: Option<DexKind>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_price: Option<f64>,
+    /// Highest `last_price` seen since entry (high‑water mark for trailing stops)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_price: Option<f64>,
+    /// Unix seconds of the first buy (0 for positions persisted before this existed)
+    #[serde(default)]
+    pub opened_at: u64,
+    /// Indices into the take‑profit ladder that already fired for this position
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub take_profit_rungs_fired: Vec<usize>,
+    /// Buys after the first entry (scale‑ins)
+    #[serde(default)]
+    pub adds: u32,
+    /// Label of the tracked wallet whose signal opened the position
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opened_by: Option<String>,
+    /// Tracked wallet label → lamports of our cost basis its signals bought;
+    /// reduced as its sells are mirrored
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub sources: HashMap<String, u64>,
+    /// Unix seconds when a liquidity pull was detected on the mint's pool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rugged_at: Option<u64>,
+    /// Lamports spent on all buys (unlike `cost_lamports`, never reduced)
+    #[serde(default)]
+    pub invested_lamports: u64,
+    /// Proceeds minus cost basis of everything sold so far
+    #[serde(default)]
+    pub realized_lamports: i64,
     pub updated_at: u64,
 }
 
@@ -38,113 +137,669 @@ impl Position {
         }
     }
 
+    /// `None` without a price or a cost basis (adopted positions).
     pub fn unrealised_pnl_pct(&self) -> Option<f64> {
         self.last_price
+            .filter(|_| self.cost_lamports > 0)
             .map(|p| ((p / self.avg_cost()) - 1.0) * 100.0)
     }
+
+    /// PnL % at the high‑water mark, i.e. the best the position has been.
+    pub fn peak_pnl_pct(&self) -> Option<f64> {
+        self.peak_price
+            .filter(|_| self.cost_lamports > 0)
+            .map(|p| ((p / self.avg_cost()) - 1.0) * 100.0)
+    }
+
+    /// Seconds since the first buy, if known.
+    pub fn held_secs(&self, now: u64) -> Option<u64> {
+        (self.opened_at > 0).then(|| now.saturating_sub(self.opened_at))
+    }
+
+    /// How far the last price sits below the peak, in % (always <= 0).
+    pub fn drawdown_from_peak_pct(&self) -> Option<f64> {
+        match (self.last_price, self.peak_price) {
+            (Some(last), Some(peak)) if peak > 0.0 => Some(((last / peak) - 1.0) * 100.0),
+            _ => None,
+        }
+    }
+
+    /// Share (0‑1) of the position that `label`'s buys are responsible for;
+    /// `None` when the position predates source tracking.
+    pub fn source_share(&self, label: &str) -> Option<f64> {
+        if self.sources.is_empty() {
+            return self
+                .opened_by
+                .as_ref()
+                .map(|opener| if opener == label { 1.0 } else { 0.0 });
+        }
+        let total: u64 = self.sources.values().sum();
+        if total == 0 {
+            return Some(0.0);
+        }
+        Some(self.sources.get(label).copied().unwrap_or(0) as f64 / total as f64)
+    }
+
+    /// A mirrored sell of `fraction` of `label`'s bag: shrink that wallet's
+    /// stake so the other sources keep their share of what is left.
+    fn release_source(&mut self, label: &str, fraction: f64) {
+        if let Some(cost) = self.sources.get_mut(label) {
+            *cost = (*cost as f64 * (1.0 - fraction.clamp(0.0, 1.0))).round() as u64;
+            if *cost == 0 {
+                self.sources.remove(label);
+            }
+        }
+    }
+}
+
+/// One mint summed over every owner holding it.
+#[derive(Debug, Clone, Default)]
+pub struct MintHolding {
+    pub mint: Pubkey,
+    /// Owners with a position in the mint
+    pub owners: usize,
+    pub balance: u128,
+    pub cost_lamports: u64,
+    /// Latest `updated_at` of the positions
+    pub updated_at: u64,
+}
+
+/// On‑disk layouts of `positions.json`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredPositions {
+    List(Vec<Position>),
+    /// mint → position, from before owners were tracked
+    ByMint(HashMap<Pubkey, Position>),
 }
 
 /* --------------------------------------------------------------------- */
 /*  Manager                                                              */
 /* --------------------------------------------------------------------- */
+/// Positions are keyed by (owner, mint): every trading keypair has its own
+/// cost basis in a mint. Per‑mint totals across owners are in [`MintHolding`].
+/// Every change to a balance is published to the [`holdings`] store.
 #[derive(Debug, Default)]
 pub struct PositionManager {
-    positions: HashMap<Pubkey, Position>,
+    positions: HashMap<(Pubkey, Pubkey), Position>,
+    wallet_stats: WalletScoreboard,
+    ledger: Ledger,
+    /// `None` keeps it in memory only (backtests)
+    path: Option<PathBuf>,
+    /// Price updates not written yet
+    dirty: bool,
+    last_persist: Option<Instant>,
+    /// Called with (mint, return %) when a position closes in full
+    close_hook: Option<fn(Pubkey, f64)>,
 }
 
 impl PositionManager {
-    pub fn load() -> io::Result<Self> {
-        let path = Path::new(STORAGE_PATH);
-        if !path.exists() {
-            return Ok(Self::default());
+    /// The live books under `workdir` (`Settings::workdir`).
+    pub fn load(workdir: &Path) -> io::Result<Self> {
+        Self::load_from(
+            &storage_path(workdir, STORAGE_FILE)?,
+            &storage_path(workdir, wallet_stats::STORAGE_FILE)?,
+            &storage_path(workdir, ledger::STORAGE_FILE)?,
+        )
+    }
+
+    /// A book that is never written to disk (backtests).
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// The paper-trading book: same accounting, separate files.
+    pub fn load_paper(workdir: &Path) -> io::Result<Self> {
+        Self::load_from(
+            &storage_path(workdir, PAPER_STORAGE_FILE)?,
+            &storage_path(workdir, wallet_stats::PAPER_STORAGE_FILE)?,
+            &storage_path(workdir, ledger::PAPER_STORAGE_FILE)?,
+        )
+    }
+
+    /// Load (and later persist to) the positions at `path`, the wallet
+    /// scoreboard at `stats_path` and the trade ledger at `ledger_path`.
+    pub fn load_from(path: &Path, stats_path: &Path, ledger_path: &Path) -> io::Result<Self> {
+        let stored = if path.exists() {
+            serde_json::from_slice(&fs::read(path)?)?
+        } else {
+            StoredPositions::List(Vec::new())
+        };
+        let positions = match stored {
+            StoredPositions::List(list) => list,
+            StoredPositions::ByMint(map) => map.into_values().collect(),
         }
-        let bytes = fs::read(path)?;
-        let map: HashMap<Pubkey, Position> = serde_json::from_slice(&bytes)?;
-        Ok(Self { positions: map })
+        .into_iter()
+        .map(|p| ((p.owner, p.mint), p))
+        .collect::<HashMap<_, _>>();
+        for &(owner, mint) in positions.keys() {
+            publish(&positions, owner, mint);
+        }
+        Ok(Self {
+            positions,
+            wallet_stats: WalletScoreboard::load_from(stats_path)?,
+            ledger: Ledger::load_from(ledger_path)?,
+            path: Some(path.to_path_buf()),
+            dirty: false,
+            last_persist: None,
+            close_hook: None,
+        })
     }
 
-    fn persist(&self) -> io::Result<()> {
-        if let Some(parent) = Path::new(STORAGE_PATH).parent() {
-            fs::create_dir_all(parent)?;
+    fn persist(&mut self) -> io::Result<()> {
+        if let Some(path) = &self.path {
+            let mut list: Vec<&Position> = self.positions.values().collect();
+            list.sort_by_key(|p| (p.owner, p.mint));
+            write_atomic(path, &serde_json::to_vec_pretty(&list)?)?;
         }
-        let json = serde_json::to_vec_pretty(&self.positions)?;
-        let mut file = fs::File::create(STORAGE_PATH)?;
-        file.write_all(&json)?;
+        self.dirty = false;
+        self.last_persist = Some(Instant::now());
         Ok(())
     }
 
+    /// Write held-back price updates once [`PRICE_FLUSH_INTERVAL`] has
+    /// passed since the last write. The strategy tick calls this.
+    pub fn flush_if_due(&mut self) -> io::Result<()> {
+        let due = self
+            .last_persist
+            .map_or(true, |at| at.elapsed() >= PRICE_FLUSH_INTERVAL);
+        if self.dirty && due {
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    /// Write held-back price updates now (e.g. on shutdown).
+    pub fn flush(&mut self) -> io::Result<()> {
+        if self.dirty {
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    /// Call `hook(mint, return_pct)` whenever a position closes in full. Only
+    /// the live book sets one, so paper and backtest closes stay local.
+    pub fn on_close(&mut self, hook: fn(Pubkey, f64)) {
+        self.close_hook = Some(hook);
+    }
+
+    /// Assign positions without an owner (persisted before owners were
+    /// tracked) to `owner`, the keypair that has been trading them.
+    pub fn claim_unowned(&mut self, owner: Pubkey) -> io::Result<()> {
+        let unowned: Vec<(Pubkey, Pubkey)> = self
+            .positions
+            .keys()
+            .filter(|(o, _)| *o == Pubkey::default())
+            .copied()
+            .collect();
+        if unowned.is_empty() || owner == Pubkey::default() {
+            return Ok(());
+        }
+        for key in unowned {
+            let mut pos = self.positions.remove(&key).expect("position present");
+            pos.owner = owner;
+            let mint = pos.mint;
+            self.positions.insert((owner, mint), pos);
+            publish(&self.positions, owner, mint);
+        }
+        self.persist()
+    }
+
     /* ------------------------------ trade recording ------------------ */
+    /// `owner` is the trading wallet that bought. `meta.wallet_label` is the
+    /// tracked wallet behind the buy (the plan's `wallet_label`); the first
+    /// one is credited with the position. Every fill is appended to the
+    /// ledger.
     pub fn record_buy(
         &mut self,
+        owner: Pubkey,
         mint: Pubkey,
+        dex: DexKind,
         qty_base_units: u128,
         cost_lamports: u64,
+        meta: &FillMeta,
     ) -> io::Result<()> {
         let now = unix_timestamp();
-        let entry = self.positions.entry(mint).or_insert(Position {
-            mint,
-            balance: 0,
-            cost_lamports: 0,
-            last_price: None,
-            updated_at: now,
-        });
+        let wallet_label = meta.wallet_label.as_deref();
+        let entry = self
+            .positions
+            .entry((owner, mint))
+            .or_insert_with(|| new_position(owner, mint, wallet_label, now));
 
+        entry.dex = Some(dex); // latest venue wins (e.g. after migration)
+        if entry.balance > 0 {
+            entry.adds += 1;
+        }
         entry.balance += qty_base_units;
         entry.cost_lamports += cost_lamports;
+        entry.invested_lamports += cost_lamports;
+        if let Some(label) = wallet_label {
+            *entry.sources.entry(label.to_string()).or_insert(0) += cost_lamports;
+        }
         entry.updated_at = now;
+        let fill = fill_entry(now, Side::Buy, mint, qty_base_units, cost_lamports, meta, entry);
+        publish(&self.positions, owner, mint);
+        self.ledger.append(fill)?;
         self.persist()
     }
 
+    /// A sell from `owner`'s position. Closing it in full books the round
+    /// trip in the wallet scoreboard under `opened_by`. Sells of an adopted
+    /// position (no cost basis) carry no realized PnL.
     pub fn record_sell(
         &mut self,
+        owner: Pubkey,
+        mint: Pubkey,
+        qty_base_units: u128,
+        received_lamports: u64,
+        meta: &FillMeta,
+    ) -> io::Result<()> {
+        let now = unix_timestamp();
+        let result = self.book_sell(owner, mint, qty_base_units, received_lamports, meta, now);
+        publish(&self.positions, owner, mint);
+        result
+    }
+
+    fn book_sell(
+        &mut self,
+        owner: Pubkey,
         mint: Pubkey,
         qty_base_units: u128,
-        _received_lamports: u64, // not used yet, kept for completeness
+        received_lamports: u64,
+        meta: &FillMeta,
+        now: u64,
     ) -> io::Result<()> {
-        if let Some(pos) = self.positions.get_mut(&mint) {
+        if let Some(pos) = self.positions.get_mut(&(owner, mint)) {
+            let basis_known = pos.cost_lamports > 0;
+            let mut entry = fill_entry(
+                now,
+                Side::Sell,
+                mint,
+                qty_base_units,
+                received_lamports,
+                meta,
+                pos,
+            );
             if qty_base_units >= pos.balance {
-                self.positions.remove(&mint);
+                let slice = received_lamports as i64 - pos.cost_lamports as i64;
+                let realized = pos.realized_lamports + slice;
+                entry.realized_lamports = basis_known.then_some(slice);
+                entry.closed = true;
+                self.ledger.append(entry)?;
+                let pos = self
+                    .positions
+                    .remove(&(owner, mint))
+                    .expect("position present");
+                if pos.invested_lamports > 0 {
+                    let return_pct = realized as f64 / pos.invested_lamports as f64 * 100.0;
+                    if let Some(hook) = self.close_hook {
+                        hook(mint, return_pct);
+                    }
+                }
+                if let Some(label) = &pos.opened_by {
+                    let hold = pos.held_secs(now).unwrap_or(0);
+                    self.wallet_stats
+                        .record_closed(label, pos.invested_lamports, realized, hold)?;
+                }
             } else {
                 let pct = qty_base_units as f64 / pos.balance as f64;
                 let reduce_cost = (pos.cost_lamports as f64 * pct).round() as u64;
 
+                // A landed follow-sell mirrored `wallet_label` selling part of
+                // its bag: we sold its share of that, so its stake shrinks by
+                // the mirrored part / share. Other strategies summed into the
+                // same sell don't count.
+                let mirrored = meta
+                    .wallet_label
+                    .as_deref()
+                    .zip(meta.follow_sell_pct)
+                    .filter(|_| meta.from_strategy("follow_sell"));
+                if let Some((label, mirrored_pct)) = mirrored {
+                    if let Some(share) = pos.source_share(label).filter(|s| *s > 0.0) {
+                        pos.release_source(label, mirrored_pct.min(pct) / share);
+                    }
+                }
+                // Ladder rungs count as taken only once their sell landed
+                if meta.from_strategy("take_profit") {
+                    for &rung in &meta.take_profit_rungs {
+                        if !pos.take_profit_rungs_fired.contains(&rung) {
+                            pos.take_profit_rungs_fired.push(rung);
+                        }
+                    }
+                }
+
                 pos.balance -= qty_base_units;
                 pos.cost_lamports -= reduce_cost;
-                pos.updated_at = unix_timestamp();
+                let slice = received_lamports as i64 - reduce_cost as i64;
+                pos.realized_lamports += slice;
+                pos.updated_at = now;
+                entry.realized_lamports = basis_known.then_some(slice);
+                self.ledger.append(entry)?;
             }
             self.persist()?;
+        } else {
+            // nothing to book against, but the fill still happened
+            let entry = LedgerEntry {
+                ts: now,
+                side: Side::Sell,
+                owner,
+                mint,
+                dex: None,
+                tokens: qty_base_units,
+                lamports: received_lamports,
+                fee_lamports: meta.fee_lamports,
+                tip_lamports: meta.tip_lamports,
+                signature: meta.signature.clone(),
+                slot: meta.slot,
+                strategy: meta.strategy.clone(),
+                wallet_label: meta.wallet_label.clone(),
+                position_opened_at: 0,
+                opened_by: None,
+                realized_lamports: None,
+                closed: false,
+            };
+            self.ledger.append(entry)?;
         }
         Ok(())
     }
 
+    /// Realized PnL of the current UTC day, from the ledger.
+    pub fn realized_today_lamports(&self) -> i64 {
+        self.ledger.realized_on_day(unix_timestamp() / 86_400)
+    }
+
     /* ------------------------------ aux helpers ---------------------- */
+    /// Price of `mint`, for every owner's position in it. Written in
+    /// batches, see [`flush_if_due`](Self::flush_if_due).
     pub fn update_price(&mut self, mint: Pubkey, price_lamports: f64) -> io::Result<()> {
-        if let Some(pos) = self.positions.get_mut(&mint) {
+        let now = unix_timestamp();
+        let mut changed = false;
+        for pos in self.positions.values_mut().filter(|p| p.mint == mint) {
             pos.last_price = Some(price_lamports);
+            if pos.peak_price.map_or(true, |peak| price_lamports > peak) {
+                pos.peak_price = Some(price_lamports);
+            }
+            pos.updated_at = now;
+            changed = true;
+        }
+        if changed {
+            self.dirty = true;
+            self.flush_if_due()?;
+        }
+        Ok(())
+    }
+
+    /// Tag every owner's position in `mint` as rugged (first detection wins).
+    pub fn mark_rugged(&mut self, mint: Pubkey) -> io::Result<()> {
+        let now = unix_timestamp();
+        let mut changed = false;
+        for pos in self.positions.values_mut().filter(|p| p.mint == mint) {
+            if pos.rugged_at.is_none() {
+                pos.rugged_at = Some(now);
+                changed = true;
+            }
+        }
+        if changed {
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    /* ------------------------------ reconciliation ------------------- */
+    /// Take over `balance` of `mint` found in `owner`'s token accounts
+    /// without a recorded buy. Its cost basis is unknown, so it has no PnL %
+    /// and its sells book no realized PnL.
+    pub fn adopt(&mut self, owner: Pubkey, mint: Pubkey, balance: u128) -> io::Result<()> {
+        let mut pos = new_position(owner, mint, None, unix_timestamp());
+        pos.balance = balance;
+        self.positions.insert((owner, mint), pos);
+        publish(&self.positions, owner, mint);
+        self.persist()
+    }
+
+    /// Drop a position whose tokens are gone from chain. Whatever moved them
+    /// was never seen, so the round trip is closed with a zero‑proceeds
+    /// `reconcile` sell in the ledger and the wallet scoreboard. It is no
+    /// trading loss, so the close hook (auto‑deny) is not told.
+    pub fn drop_position(&mut self, owner: Pubkey, mint: Pubkey) -> io::Result<Option<Position>> {
+        let pos = match self.positions.get(&(owner, mint)) {
+            Some(pos) => pos.clone(),
+            None => return Ok(None),
+        };
+        let meta = FillMeta {
+            strategy: Some("reconcile".to_string()),
+            ..FillMeta::default()
+        };
+        let hook = self.close_hook.take();
+        let result = self.book_sell(owner, mint, pos.balance, 0, &meta, unix_timestamp());
+        self.close_hook = hook;
+        publish(&self.positions, owner, mint);
+        result.map(|()| Some(pos))
+    }
+
+    /// Set a position's balance to the on‑chain amount. The cost basis is
+    /// scaled with it, keeping the average cost.
+    pub fn set_balance(&mut self, owner: Pubkey, mint: Pubkey, balance: u128) -> io::Result<()> {
+        if let Some(pos) = self.positions.get_mut(&(owner, mint)) {
+            if pos.balance > 0 {
+                pos.cost_lamports =
+                    (pos.cost_lamports as f64 * balance as f64 / pos.balance as f64).round() as u64;
+            }
+            pos.balance = balance;
             pos.updated_at = unix_timestamp();
+            publish(&self.positions, owner, mint);
             self.persist()?;
         }
         Ok(())
     }
 
-    pub fn unrealised_pct(&self, mint: Pubkey) -> Option<f64> {
+    pub fn unrealised_pct(&self, owner: Pubkey, mint: Pubkey) -> Option<f64> {
         self.positions
-            .get(&mint)
+            .get(&(owner, mint))
             .and_then(|p| p.unrealised_pnl_pct())
     }
 
-    pub fn balance(&self, mint: Pubkey) -> u128 {
-        self.positions.get(&mint).map(|p| p.balance).unwrap_or(0)
+    pub fn position(&self, owner: Pubkey, mint: Pubkey) -> Option<&Position> {
+        self.positions.get(&(owner, mint))
     }
 
+    pub fn balance(&self, owner: Pubkey, mint: Pubkey) -> u128 {
+        self.positions
+            .get(&(owner, mint))
+            .map(|p| p.balance)
+            .unwrap_or(0)
+    }
+
+    /// Every owner's positions.
     pub fn iter(&self) -> impl Iterator<Item = &Position> {
         self.positions.values()
     }
+
+    /// Positions of one trading wallet.
+    pub fn owned_by(&self, owner: Pubkey) -> impl Iterator<Item = &Position> {
+        self.positions.values().filter(move |p| p.owner == owner)
+    }
+
+    /* ------------------------------ views across owners -------------- */
+    /// `mint` summed over every owner, if anyone has a position in it.
+    pub fn holding(&self, mint: Pubkey) -> Option<MintHolding> {
+        self.holdings().into_iter().find(|h| h.mint == mint)
+    }
+
+    /// Every mint with a position, summed over owners.
+    pub fn holdings(&self) -> Vec<MintHolding> {
+        let mut by_mint: HashMap<Pubkey, MintHolding> = HashMap::new();
+        for pos in self.positions.values() {
+            let h = by_mint.entry(pos.mint).or_insert_with(|| MintHolding {
+                mint: pos.mint,
+                ..MintHolding::default()
+            });
+            h.owners += 1;
+            h.balance += pos.balance;
+            h.cost_lamports += pos.cost_lamports;
+            h.updated_at = h.updated_at.max(pos.updated_at);
+        }
+        by_mint.into_values().collect()
+    }
+
+    /// Mints with a non‑zero balance, counted once however many owners
+    /// hold them.
+    pub fn open_count(&self) -> usize {
+        self.holdings().iter().filter(|h| h.balance > 0).count()
+    }
+
+    /// Cost basis of all open positions, across owners.
+    pub fn exposure_lamports(&self) -> u64 {
+        self.positions.values().map(|p| p.cost_lamports).sum()
+    }
+
+    pub fn wallet_stats(&self) -> &WalletScoreboard {
+        &self.wallet_stats
+    }
+
+    pub fn ledger(&self) -> &Ledger {
+        &self.ledger
+    }
+}
+
+/// Make the holdings store show `owner`'s position in `mint` as held, or
+/// not held once it is gone or empty.
+fn publish(positions: &HashMap<(Pubkey, Pubkey), Position>, owner: Pubkey, mint: Pubkey) {
+    if owner == Pubkey::default() {
+        return;
+    }
+    match positions.get(&(owner, mint)).filter(|p| p.balance > 0) {
+        Some(pos) => {
+            let amount = pos.balance.min(u64::MAX as u128) as u64;
+            holdings::confirm(&owner, &mint, Some(amount))
+        }
+        None => holdings::clear(&owner, &mint),
+    }
+}
+
+/// An empty position, opened now.
+pub(crate) fn new_position(
+    owner: Pubkey,
+    mint: Pubkey,
+    opened_by: Option<&str>,
+    now: u64,
+) -> Position {
+    Position {
+        owner,
+        mint,
+        balance: 0,
+        cost_lamports: 0,
+        dex: None,
+        last_price: None,
+        peak_price: None,
+        opened_at: now,
+        take_profit_rungs_fired: Vec::new(),
+        adds: 0,
+        opened_by: opened_by.map(str::to_string),
+        sources: HashMap::new(),
+        rugged_at: None,
+        invested_lamports: 0,
+        realized_lamports: 0,
+        updated_at: now,
+    }
+}
+
+/// Ledger line for a fill on `pos` (realized PnL is set by the caller).
+fn fill_entry(
+    ts: u64,
+    side: Side,
+    mint: Pubkey,
+    tokens: u128,
+    lamports: u64,
+    meta: &FillMeta,
+    pos: &Position,
+) -> LedgerEntry {
+    LedgerEntry {
+        ts,
+        side,
+        owner: pos.owner,
+        mint,
+        dex: pos.dex,
+        tokens,
+        lamports,
+        fee_lamports: meta.fee_lamports,
+        tip_lamports: meta.tip_lamports,
+        signature: meta.signature.clone(),
+        slot: meta.slot,
+        strategy: meta.strategy.clone(),
+        wallet_label: meta.wallet_label.clone(),
+        position_opened_at: pos.opened_at,
+        opened_by: pos.opened_by.clone(),
+        realized_lamports: None,
+        closed: false,
+    }
+}
+
+/// Simulated time set by a backtest; 0 means the system clock.
+static SIMULATED_TIME: AtomicU64 = AtomicU64::new(0);
+
+/// Make [`unix_timestamp`] return `ts` (replayed event time) until reset
+/// with `None`. Only backtests should call this.
+pub fn set_simulated_time(ts: Option<u64>) {
+    SIMULATED_TIME.store(ts.unwrap_or(0), Ordering::Relaxed);
 }
 
-fn unix_timestamp() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0)
+pub fn unix_timestamp() -> u64 {
+    match SIMULATED_TIME.load(Ordering::Relaxed) {
+        0 => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        ts => ts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(cost_lamports: u64, balance: u128) -> Position {
+        let mut pos = new_position(Pubkey::new_unique(), Pubkey::new_unique(), None, 0);
+        pos.balance = balance;
+        pos.cost_lamports = cost_lamports;
+        pos
+    }
+
+    #[test]
+    fn test_drawdown_from_peak() {
+        let mut pos = position(1_000, 1_000); // avg cost 1.0
+        assert_eq!(pos.drawdown_from_peak_pct(), None);
+
+        pos.peak_price = Some(3.0);
+        pos.last_price = Some(2.4);
+        assert!((pos.peak_pnl_pct().unwrap() - 200.0).abs() < 1e-9);
+        assert!((pos.drawdown_from_peak_pct().unwrap() + 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_follow_sell_releases_source_when_booked() {
+        let (owner, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut pm = PositionManager::in_memory();
+        for (label, cost) in [("a", 600), ("b", 400)] {
+            let meta = FillMeta {
+                wallet_label: Some(label.to_string()),
+                ..FillMeta::default()
+            };
+            pm.record_buy(owner, mint, DexKind::Pumpfun, cost as u128, cost, &meta)
+                .unwrap();
+        }
+
+        // "a" sold half its bag: we mirror 30 %, summed with a 20 % take
+        // profit. Only the mirrored part halves its stake.
+        let meta = FillMeta {
+            strategy: Some("take_profit+follow_sell".to_string()),
+            wallet_label: Some("a".to_string()),
+            follow_sell_pct: Some(0.3),
+            ..FillMeta::default()
+        };
+        pm.record_sell(owner, mint, 500, 500, &meta).unwrap();
+        let pos = pm.position(owner, mint).unwrap();
+        assert_eq!(pos.sources["a"], 300);
+        assert_eq!(pos.sources["b"], 400);
+    }
 }
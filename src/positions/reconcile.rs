@@ -0,0 +1,328 @@
+//! Reconciliation of the books against the trading wallet's token accounts.
+//!
+//! The `PositionManager` is persisted, but the holdings store
+//! ([`super::holdings`]) lives in memory and only has what the book seeded
+//! it with after a restart, and both drift when tokens move outside the bot.
+//! [`reconcile`] lists the wallet's SPL Token and Token‑2022 accounts over
+//! RPC and makes both match the chain:
+//!
+//! * tokens without a position are adopted (cost basis unknown),
+//! * positions without tokens are dropped,
+//! * balances and confirmed holdings are set to what is held.
+//!
+//! `StrategyEngine::start` runs it on boot, before the listener starts; run
+//! it on demand through [`reconcile_engine`] while no trades are in flight – a fill landing
+//! between the RPC listing and the repair would be undone.
+
+use anyhow::{anyhow, Result};
+use solana_account_decoder::{UiAccount, UiAccountData};
+use solana_client::{rpc_client::RpcClient, rpc_request::TokenAccountsFilter};
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashMap, fmt, sync::Mutex};
+
+use super::holdings::{self, Holding};
+use super::PositionManager;
+use crate::config::settings::Settings;
+use crate::strategy::engine::STRATEGY_ENGINE;
+
+/* --------------------------------------------------------------------- */
+/*  Repairs                                                              */
+/* --------------------------------------------------------------------- */
+/// One difference between the books and the chain, and how it was fixed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Repair {
+    /// Tokens on chain without a position: adopted
+    Adopted { mint: Pubkey, amount: u64 },
+    /// Position without tokens on chain: dropped
+    Dropped { mint: Pubkey, balance: u128 },
+    /// Position balance set to the on‑chain amount
+    Balance { mint: Pubkey, from: u128, to: u64 },
+    /// Holdings store entry confirmed with the on‑chain amount
+    Holding {
+        mint: Pubkey,
+        from: Option<u64>,
+        to: u64,
+    },
+    /// Holdings store entry of a mint no longer held removed
+    HoldingCleared { mint: Pubkey },
+}
+
+impl Repair {
+    pub fn mint(&self) -> Pubkey {
+        match self {
+            Repair::Adopted { mint, .. }
+            | Repair::Dropped { mint, .. }
+            | Repair::Balance { mint, .. }
+            | Repair::Holding { mint, .. }
+            | Repair::HoldingCleared { mint } => *mint,
+        }
+    }
+}
+
+impl fmt::Display for Repair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Repair::Adopted { mint, amount } => {
+                write!(f, "adopted {} tokens of {} (no cost basis)", amount, mint)
+            }
+            Repair::Dropped { mint, balance } => {
+                write!(
+                    f,
+                    "dropped position in {} ({} tokens not on chain)",
+                    mint, balance
+                )
+            }
+            Repair::Balance { mint, from, to } => {
+                write!(f, "balance of {} corrected {} → {}", mint, from, to)
+            }
+            Repair::Holding { mint, from, to } => match from {
+                Some(from) => write!(f, "holding of {} corrected {} → {}", mint, from, to),
+                None => write!(f, "holding of {} confirmed: {}", mint, to),
+            },
+            Repair::HoldingCleared { mint } => write!(f, "holding of {} cleared", mint),
+        }
+    }
+}
+
+/* --------------------------------------------------------------------- */
+/*  On‑chain balances                                                    */
+/* --------------------------------------------------------------------- */
+/// Non‑zero SPL Token and Token‑2022 balances of `owner` by mint, in base
+/// units. Several accounts of one mint are summed; wrapped SOL is left out.
+pub fn fetch_token_balances(rpc: &RpcClient, owner: &Pubkey) -> Result<HashMap<Pubkey, u64>> {
+    let mut out = HashMap::new();
+    for program in [spl_token::id(), spl_token_2022::id()] {
+        let accounts = rpc
+            .get_token_accounts_by_owner(owner, TokenAccountsFilter::ProgramId(program))
+            .map_err(|e| anyhow!("listing token accounts of {} ({}): {}", owner, program, e))?;
+        for keyed in accounts {
+            match parse_token_account(&keyed.account) {
+                Some((mint, amount)) => {
+                    if amount > 0 && mint != spl_token::native_mint::id() {
+                        *out.entry(mint).or_insert(0) += amount;
+                    }
+                }
+                None => println!(
+                    "⚠️ [RECONCILE] Could not parse token account {}",
+                    keyed.pubkey
+                ),
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Mint and amount of a `jsonParsed` token account.
+fn parse_token_account(account: &UiAccount) -> Option<(Pubkey, u64)> {
+    let parsed = match &account.data {
+        UiAccountData::Json(parsed) => &parsed.parsed,
+        _ => return None,
+    };
+    let info = &parsed["info"];
+    let mint = info["mint"].as_str()?.parse().ok()?;
+    let amount = info["tokenAmount"]["amount"].as_str()?.parse().ok()?;
+    Some((mint, amount))
+}
+
+/* --------------------------------------------------------------------- */
+/*  Diffing                                                              */
+/* --------------------------------------------------------------------- */
+/// What it takes to make `owner`'s positions match `held`.
+pub fn position_repairs(
+    pm: &PositionManager,
+    owner: Pubkey,
+    held: &HashMap<Pubkey, u64>,
+) -> Vec<Repair> {
+    let mut out = Vec::new();
+    for pos in pm.owned_by(owner) {
+        match held.get(&pos.mint) {
+            None => out.push(Repair::Dropped {
+                mint: pos.mint,
+                balance: pos.balance,
+            }),
+            Some(&amount) if amount as u128 != pos.balance => out.push(Repair::Balance {
+                mint: pos.mint,
+                from: pos.balance,
+                to: amount,
+            }),
+            Some(_) => {}
+        }
+    }
+    for (&mint, &amount) in held {
+        if pm.position(owner, mint).is_none() {
+            out.push(Repair::Adopted { mint, amount });
+        }
+    }
+    out.sort_by_key(Repair::mint);
+    out
+}
+
+/// What it takes to make the wallet's entries in the holdings store match
+/// `held`. Buys still pending are left alone.
+pub fn holding_repairs(
+    store: &HashMap<Pubkey, Holding>,
+    held: &HashMap<Pubkey, u64>,
+) -> Vec<Repair> {
+    let mut out = Vec::new();
+    for (&mint, &amount) in held {
+        let current = store.get(&mint);
+        if !current.map_or(false, |h| h.confirmed && h.amount == Some(amount)) {
+            out.push(Repair::Holding {
+                mint,
+                from: current.and_then(|h| h.amount),
+                to: amount,
+            });
+        }
+    }
+    for (&mint, h) in store {
+        if !held.contains_key(&mint) && (h.confirmed || h.amount.is_some()) {
+            out.push(Repair::HoldingCleared { mint });
+        }
+    }
+    out.sort_by_key(Repair::mint);
+    out
+}
+
+/* --------------------------------------------------------------------- */
+/*  Entry points                                                         */
+/* --------------------------------------------------------------------- */
+/// Reconcile the active wallet's books in `positions` against the chain and
+/// return the repairs made; with `dry_run` they are only reported. Paper
+/// positions are not on chain, so paper trading leaves everything as is.
+pub fn reconcile(
+    settings: &Settings,
+    positions: &Mutex<PositionManager>,
+    dry_run: bool,
+) -> Result<Vec<Repair>> {
+    if settings.paper_trading {
+        println!("📝 [RECONCILE] Paper trading – nothing on chain to reconcile against");
+        return Ok(Vec::new());
+    }
+    let owner = settings.owner();
+    let held = fetch_token_balances(&settings.rpc_client, &owner)?;
+    println!(
+        "🔍 [RECONCILE] {} holds {} mints on chain",
+        owner,
+        held.len()
+    );
+
+    let mut repairs = {
+        let mut pm = positions.lock().unwrap();
+        let repairs = position_repairs(&pm, owner, &held);
+        if !dry_run {
+            for repair in &repairs {
+                match *repair {
+                    Repair::Adopted { mint, amount } => pm.adopt(owner, mint, amount as u128)?,
+                    Repair::Dropped { mint, .. } => {
+                        pm.drop_position(owner, mint)?;
+                    }
+                    Repair::Balance { mint, to, .. } => pm.set_balance(owner, mint, to as u128)?,
+                    _ => {}
+                }
+            }
+        }
+        repairs
+    };
+
+    // the book publishes its repairs; this catches what it doesn't know of
+    let store = holding_repairs(&holdings::for_wallet(&owner), &held);
+    if !dry_run {
+        for repair in &store {
+            match *repair {
+                Repair::Holding { mint, to, .. } => holdings::confirm(&owner, &mint, Some(to)),
+                Repair::HoldingCleared { mint } => holdings::clear(&owner, &mint),
+                _ => {}
+            }
+        }
+    }
+    repairs.extend(store);
+
+    for repair in &repairs {
+        println!("🔧 [RECONCILE] {}", repair);
+    }
+    if dry_run {
+        println!(
+            "✅ [RECONCILE] {} repairs needed (dry run, nothing changed)",
+            repairs.len()
+        );
+    } else {
+        println!("✅ [RECONCILE] {} repairs made", repairs.len());
+    }
+    Ok(repairs)
+}
+
+/// [`reconcile`] the books of the running strategy engine.
+pub fn reconcile_engine(settings: &Settings, dry_run: bool) -> Result<Vec<Repair>> {
+    let engine = STRATEGY_ENGINE
+        .get()
+        .ok_or_else(|| anyhow!("strategy engine not started"))?;
+    reconcile(settings, &engine.positions, dry_run)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::positions::ledger::FillMeta;
+    use crate::strategy::DexKind;
+
+    #[test]
+    fn test_position_repairs() {
+        let owner = Pubkey::new_unique();
+        let (kept, short, gone, new) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let mut pm = PositionManager::in_memory();
+        for mint in [kept, short, gone] {
+            pm.record_buy(
+                owner,
+                mint,
+                DexKind::Pumpfun,
+                100,
+                1_000,
+                &FillMeta::default(),
+            )
+            .unwrap();
+        }
+        let held = HashMap::from([(kept, 100), (short, 40), (new, 7)]);
+
+        let mut repairs = position_repairs(&pm, owner, &held);
+        repairs.sort_by_key(|r| r.to_string());
+        let mut expected = vec![
+            Repair::Balance {
+                mint: short,
+                from: 100,
+                to: 40,
+            },
+            Repair::Dropped {
+                mint: gone,
+                balance: 100,
+            },
+            Repair::Adopted {
+                mint: new,
+                amount: 7,
+            },
+        ];
+        expected.sort_by_key(|r| r.to_string());
+        assert_eq!(repairs, expected);
+
+        pm.set_balance(owner, short, 40).unwrap();
+        assert_eq!(pm.position(owner, short).unwrap().cost_lamports, 400);
+        pm.adopt(owner, new, 7).unwrap();
+        pm.update_price(new, 2.0).unwrap();
+        assert_eq!(pm.unrealised_pct(owner, new), None);
+        pm.drop_position(owner, gone).unwrap();
+        let closing = pm.ledger().entries().last().unwrap();
+        assert!(closing.closed && closing.mint == gone);
+        assert_eq!(closing.lamports, 0);
+        assert_eq!(closing.realized_lamports, Some(-1_000));
+
+        // the book keeps the holdings store in step
+        assert_eq!(holdings::amount(&owner, &short), Some(40));
+        assert_eq!(holdings::amount(&owner, &new), Some(7));
+        assert!(!holdings::is_held(&owner, &gone));
+        assert!(holding_repairs(&holdings::for_wallet(&owner), &held).is_empty());
+    }
+}
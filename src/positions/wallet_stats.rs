@@ -0,0 +1,207 @@
+//! Per‑wallet performance, fed by positions closing.
+//!
+//! Every position remembers the tracked wallet whose signal opened it; when it
+//! is fully sold the round trip is booked here under that label. The
+//! resulting score scales the wallet's `buy_amount_sol` and can switch the
+//! wallet off (see [`WalletScoringConfig`]).
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::{unix_timestamp, write_atomic};
+use crate::config::settings::WalletScoringConfig;
+
+/* --------------------------------------------------------------------- */
+/*  On‑disk location                                                     */
+/* --------------------------------------------------------------------- */
+pub(crate) const STORAGE_FILE: &str = "wallet_stats.json";
+/// Scoreboard of the paper-trading positions
+pub(crate) const PAPER_STORAGE_FILE: &str = "paper_wallet_stats.json";
+
+/* --------------------------------------------------------------------- */
+/*  Stats of one tracked wallet                                          */
+/* --------------------------------------------------------------------- */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WalletStats {
+    /// Closed round trips
+    pub trades: u32,
+    /// Round trips that returned more than they cost
+    pub wins: u32,
+    pub realized_pnl_lamports: i64,
+    pub invested_lamports: u64,
+    /// Sum of per‑trade returns in %, for the average
+    pub total_return_pct: f64,
+    pub total_hold_secs: u64,
+    pub updated_at: u64,
+}
+
+impl WalletStats {
+    pub fn win_rate(&self) -> f64 {
+        if self.trades == 0 {
+            0.0
+        } else {
+            self.wins as f64 / self.trades as f64
+        }
+    }
+
+    pub fn avg_return_pct(&self) -> f64 {
+        if self.trades == 0 {
+            0.0
+        } else {
+            self.total_return_pct / self.trades as f64
+        }
+    }
+
+    pub fn avg_hold_secs(&self) -> u64 {
+        if self.trades == 0 {
+            0
+        } else {
+            self.total_hold_secs / self.trades as u64
+        }
+    }
+}
+
+/* --------------------------------------------------------------------- */
+/*  Scoreboard                                                           */
+/* --------------------------------------------------------------------- */
+#[derive(Debug, Default)]
+pub struct WalletScoreboard {
+    wallets: HashMap<String, WalletStats>,
+    /// `None` keeps it in memory only (backtests)
+    path: Option<PathBuf>,
+}
+
+impl WalletScoreboard {
+    /// Load (and later persist to) the scoreboard at `path`.
+    pub fn load_from(path: &Path) -> io::Result<Self> {
+        let wallets = if path.exists() {
+            serde_json::from_slice(&fs::read(path)?)?
+        } else {
+            HashMap::new()
+        };
+        Ok(Self {
+            wallets,
+            path: Some(path.to_path_buf()),
+        })
+    }
+
+    fn persist(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        write_atomic(path, &serde_json::to_vec_pretty(&self.wallets)?)
+    }
+
+    /// Book a fully closed position under the wallet that opened it.
+    pub fn record_closed(
+        &mut self,
+        label: &str,
+        invested_lamports: u64,
+        realized_pnl_lamports: i64,
+        hold_secs: u64,
+    ) -> io::Result<()> {
+        let stats = self.wallets.entry(label.to_string()).or_default();
+        stats.trades += 1;
+        if realized_pnl_lamports > 0 {
+            stats.wins += 1;
+        }
+        stats.realized_pnl_lamports += realized_pnl_lamports;
+        stats.invested_lamports += invested_lamports;
+        if invested_lamports > 0 {
+            stats.total_return_pct += realized_pnl_lamports as f64 / invested_lamports as f64 * 100.0;
+        }
+        stats.total_hold_secs += hold_secs;
+        stats.updated_at = unix_timestamp();
+
+        println!(
+            "📊 [WALLET_SCORE] {} closed trade: {:+.4} SOL | {} trades, win rate {:.0}%, avg {:+.1}%",
+            label,
+            realized_pnl_lamports as f64 / 1_000_000_000.0,
+            stats.trades,
+            stats.win_rate() * 100.0,
+            stats.avg_return_pct()
+        );
+        self.persist()
+    }
+
+    pub fn get(&self, label: &str) -> Option<&WalletStats> {
+        self.wallets.get(label)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &WalletStats)> {
+        self.wallets.iter()
+    }
+
+    /// Stats that are mature enough to act on under `cfg`.
+    fn scored(&self, label: &str, cfg: &WalletScoringConfig) -> Option<&WalletStats> {
+        if !cfg.enabled {
+            return None;
+        }
+        self.get(label).filter(|s| s.trades >= cfg.min_trades)
+    }
+
+    /// Multiplier for the wallet's `buy_amount_sol`: `1 + avg return`,
+    /// clamped to the configured bounds; 1.0 until enough trades closed.
+    pub fn size_multiplier(&self, label: &str, cfg: &WalletScoringConfig) -> f64 {
+        match self.scored(label, cfg) {
+            Some(stats) => (1.0 + stats.avg_return_pct() / 100.0)
+                .clamp(cfg.min_size_multiplier, cfg.max_size_multiplier),
+            None => 1.0,
+        }
+    }
+
+    /// Why the wallet is auto‑disabled, if it is.
+    pub fn disabled_reason(&self, label: &str, cfg: &WalletScoringConfig) -> Option<String> {
+        let stats = self.scored(label, cfg)?;
+        if let Some(min) = cfg.disable_below_win_rate {
+            if stats.win_rate() < min {
+                return Some(format!(
+                    "win rate {:.0}% < {:.0}%",
+                    stats.win_rate() * 100.0,
+                    min * 100.0
+                ));
+            }
+        }
+        if let Some(min) = cfg.disable_below_avg_return_pct {
+            if stats.avg_return_pct() < min {
+                return Some(format!("avg return {:.1}% < {:.1}%", stats.avg_return_pct(), min));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_multiplier_and_disable() {
+        let cfg = WalletScoringConfig {
+            enabled: true,
+            min_trades: 2,
+            min_size_multiplier: 0.5,
+            max_size_multiplier: 2.0,
+            disable_below_win_rate: Some(0.4),
+            disable_below_avg_return_pct: None,
+        };
+        let mut board = WalletScoreboard::default();
+        let stats = board.wallets.entry("w".into()).or_default();
+        stats.trades = 1;
+        stats.total_return_pct = -80.0;
+        // not enough trades yet
+        assert_eq!(board.size_multiplier("w", &cfg), 1.0);
+        assert!(board.disabled_reason("w", &cfg).is_none());
+
+        let stats = board.wallets.get_mut("w").unwrap();
+        stats.trades = 2;
+        stats.total_return_pct = -160.0;
+        assert_eq!(board.size_multiplier("w", &cfg), 0.5);
+        assert!(board.disabled_reason("w", &cfg).is_some());
+    }
+}
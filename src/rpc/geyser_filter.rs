@@ -0,0 +1,133 @@
+//! Accounts the Geyser subscription filters on.
+//!
+//! Transactions: the enabled tracked wallets are always included. Other parts
+//! of the bot (e.g. `strategy::creator_watch`) add accounts at runtime under
+//! their own name. Account updates: watchers such as `strategy::rug_watch`
+//! subscribe to accounts whose data they decode (pool reserve vaults).
+//!
+//! [`version`] changes with every watch change, so a listener can re-send
+//! its subscribe request with [`accounts`] and [`account_subscriptions`]
+//! when it moves and keep one stream. `rpc::geyser_listener` does not do
+//! that yet: until it does, watchers' accounts never reach the stream.
+
+use once_cell::sync::Lazy;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::RwLock;
+
+use crate::config::settings::Settings;
+
+#[derive(Debug, Default)]
+struct DynamicFilter {
+    /// Bumped on every change of `watches` or `account_watches`
+    version: u64,
+    /// watcher name → accounts whose transactions it wants in the stream
+    watches: HashMap<String, HashSet<Pubkey>>,
+    /// watcher name → accounts whose updates it wants in the stream
+    account_watches: HashMap<String, HashSet<Pubkey>>,
+}
+
+static FILTER: Lazy<RwLock<DynamicFilter>> = Lazy::new(|| RwLock::new(DynamicFilter::default()));
+
+/// Replace the accounts whose transactions `watcher` wants. Returns whether
+/// the filter changed (and the listener should resubscribe).
+pub fn set_watch(watcher: &str, accounts: HashSet<Pubkey>) -> bool {
+    let mut filter = FILTER.write().unwrap();
+    let filter = &mut *filter;
+    replace(
+        &mut filter.watches,
+        &mut filter.version,
+        watcher,
+        accounts,
+        "transactions",
+    )
+}
+
+/// Replace the accounts whose updates `watcher` wants, see [`set_watch`].
+pub fn set_account_watch(watcher: &str, accounts: HashSet<Pubkey>) -> bool {
+    let mut filter = FILTER.write().unwrap();
+    let filter = &mut *filter;
+    replace(
+        &mut filter.account_watches,
+        &mut filter.version,
+        watcher,
+        accounts,
+        "updates",
+    )
+}
+
+fn replace(
+    watches: &mut HashMap<String, HashSet<Pubkey>>,
+    version: &mut u64,
+    watcher: &str,
+    accounts: HashSet<Pubkey>,
+    what: &str,
+) -> bool {
+    let unchanged = match watches.get(watcher) {
+        Some(prev) => *prev == accounts,
+        None => accounts.is_empty(),
+    };
+    if unchanged {
+        return false;
+    }
+
+    println!(
+        "📡 [GEYSER_FILTER] {} now watches {} account(s) for {}",
+        watcher,
+        accounts.len(),
+        what
+    );
+    if accounts.is_empty() {
+        watches.remove(watcher);
+    } else {
+        watches.insert(watcher.to_string(), accounts);
+    }
+    *version += 1;
+    true
+}
+
+/// Current filter version; changes whenever a watcher's accounts change.
+pub fn version() -> u64 {
+    FILTER.read().unwrap().version
+}
+
+/// Watchers that asked for `account`, so the listener can route its
+/// transactions to them instead of treating it as a tracked wallet.
+pub fn watchers_of(account: &Pubkey) -> Vec<String> {
+    let filter = FILTER.read().unwrap();
+    let mut out: Vec<String> = filter
+        .watches
+        .iter()
+        .filter(|(_, accounts)| accounts.contains(account))
+        .map(|(watcher, _)| watcher.clone())
+        .collect();
+    out.sort();
+    out
+}
+
+/// Every account to put in the subscription's `account_include`: the
+/// enabled tracked wallets plus all runtime watches, deduplicated.
+pub fn accounts(settings: &Settings) -> Vec<String> {
+    let filter = FILTER.read().unwrap();
+    let mut out: BTreeSet<String> = settings
+        .enabled_wallets()
+        .into_iter()
+        .map(|w| w.address.clone())
+        .collect();
+    for accounts in filter.watches.values() {
+        out.extend(accounts.iter().map(|a| a.to_string()));
+    }
+    out.into_iter().collect()
+}
+
+/// Every account to subscribe to updates of, deduplicated.
+pub fn account_subscriptions() -> Vec<String> {
+    let filter = FILTER.read().unwrap();
+    let out: BTreeSet<String> = filter
+        .account_watches
+        .values()
+        .flatten()
+        .map(|a| a.to_string())
+        .collect();
+    out.into_iter().collect()
+}
@@ -0,0 +1,396 @@
+//! Record a Geyser stream to disk and serve it back.
+//!
+//! A recording is a file starting with [`MAGIC`], followed by length-delimited
+//! protobuf [`RecordedUpdate`]s: every raw `SubscribeUpdate` as received, with
+//! its receive time. [`ReplayServer`] implements the Geyser gRPC service and
+//! streams a recording to any subscriber – the bot, `test_mempool`, a test –
+//! in recorded order, at recorded pace (scaled by `speed`) or as fast as the
+//! client reads. The subscribe request is ignored: a replay serves exactly
+//! what was recorded, so the same file always produces the same stream.
+
+use anyhow::{anyhow, bail, Context, Result};
+use prost::Message;
+use std::{
+    collections::HashMap,
+    fs,
+    io::{BufWriter, Write},
+    net::SocketAddr,
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+use tonic::{
+    metadata::MetadataValue,
+    transport::{ClientTlsConfig, Endpoint, Server},
+    Request, Response, Status, Streaming,
+};
+
+use crate::rpc::geyser::geyser::{
+    geyser_client::GeyserClient,
+    geyser_server::{Geyser, GeyserServer},
+    GetBlockHeightRequest, GetBlockHeightResponse, GetLatestBlockhashRequest,
+    GetLatestBlockhashResponse, GetSlotRequest, GetSlotResponse, GetVersionRequest,
+    GetVersionResponse, IsBlockhashValidRequest, IsBlockhashValidResponse, PingRequest,
+    PongResponse, SubscribeRequest, SubscribeRequestFilterAccounts,
+    SubscribeRequestFilterTransactions, SubscribeUpdate,
+};
+
+/// First bytes of every recording (format version 1).
+pub const MAGIC: &[u8; 8] = b"GSRREC1\n";
+
+/// One received message.
+#[derive(Clone, PartialEq, Message)]
+pub struct RecordedUpdate {
+    /// Receive time, microseconds since the Unix epoch
+    #[prost(uint64, tag = "1")]
+    pub received_at_us: u64,
+    #[prost(message, optional, tag = "2")]
+    pub update: Option<SubscribeUpdate>,
+}
+
+fn now_us() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_micros() as u64)
+}
+
+/* --------------------------------------------------------------------- */
+/*  File format                                                          */
+/* --------------------------------------------------------------------- */
+
+/// Appends updates to a recording.
+pub struct RecordingWriter {
+    out: BufWriter<fs::File>,
+    buf: Vec<u8>,
+    count: u64,
+}
+
+impl RecordingWriter {
+    /// Create (or truncate) the recording at `path`.
+    pub fn create(path: &Path) -> Result<Self> {
+        let file =
+            fs::File::create(path).with_context(|| format!("creating {}", path.display()))?;
+        let mut out = BufWriter::new(file);
+        out.write_all(MAGIC)?;
+        Ok(Self {
+            out,
+            buf: Vec::new(),
+            count: 0,
+        })
+    }
+
+    /// Append `update`, stamped with the current time.
+    pub fn record(&mut self, update: SubscribeUpdate) -> Result<()> {
+        self.write(&RecordedUpdate {
+            received_at_us: now_us(),
+            update: Some(update),
+        })
+    }
+
+    pub fn write(&mut self, rec: &RecordedUpdate) -> Result<()> {
+        self.buf.clear();
+        rec.encode_length_delimited(&mut self.buf)?;
+        self.out.write_all(&self.buf)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Updates written so far.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Read a whole recording. A record cut short at the end (the recorder was
+/// killed mid-write) is dropped with a warning.
+pub fn read_recording(path: &Path) -> Result<Vec<RecordedUpdate>> {
+    let raw = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let mut data = raw
+        .strip_prefix(MAGIC.as_slice())
+        .ok_or_else(|| anyhow!("{} is not a Geyser recording", path.display()))?;
+
+    let mut out = Vec::new();
+    while !data.is_empty() {
+        match RecordedUpdate::decode_length_delimited(&mut data) {
+            Ok(rec) => out.push(rec),
+            Err(e) => {
+                println!(
+                    "⚠️ [GEYSER_RECORDING] {} truncated after {} updates: {}",
+                    path.display(),
+                    out.len(),
+                    e
+                );
+                break;
+            }
+        }
+    }
+    Ok(out)
+}
+
+/* --------------------------------------------------------------------- */
+/*  Recording                                                            */
+/* --------------------------------------------------------------------- */
+
+/// Subscribe to `url` for transactions touching `accounts` and updates of
+/// `account_updates`, and write every message to `writer` until the stream
+/// ends or `stop` resolves.
+pub async fn record(
+    url: &str,
+    token: Option<&str>,
+    accounts: Vec<String>,
+    account_updates: Vec<String>,
+    writer: &mut RecordingWriter,
+    stop: impl std::future::Future<Output = ()>,
+) -> Result<()> {
+    if accounts.is_empty() && account_updates.is_empty() {
+        bail!("nothing to record: no accounts given");
+    }
+
+    let mut endpoint = Endpoint::from_shared(url.to_string())?;
+    if url.starts_with("https") {
+        endpoint = endpoint.tls_config(ClientTlsConfig::new())?;
+    }
+    let channel = endpoint.connect().await?;
+    let token: Option<MetadataValue<_>> = token.map(|t| t.parse()).transpose()?;
+    let mut client = GeyserClient::with_interceptor(channel, move |mut req: Request<()>| {
+        if let Some(token) = &token {
+            req.metadata_mut().insert("x-token", token.clone());
+        }
+        Ok(req)
+    });
+
+    let mut transactions = HashMap::new();
+    if !accounts.is_empty() {
+        transactions.insert(
+            "recorded_transactions".to_string(),
+            SubscribeRequestFilterTransactions {
+                account_include: accounts,
+                account_exclude: vec![],
+                account_required: vec![],
+                signature: None,
+                vote: Some(false),
+                failed: None,
+            },
+        );
+    }
+    let mut account_filters = HashMap::new();
+    if !account_updates.is_empty() {
+        account_filters.insert(
+            "recorded_accounts".to_string(),
+            SubscribeRequestFilterAccounts {
+                account: account_updates,
+                owner: vec![],
+                filters: vec![],
+                nonempty_txn_signature: None,
+            },
+        );
+    }
+
+    let (req_tx, req_rx) = tokio::sync::mpsc::channel(8);
+    req_tx
+        .send(SubscribeRequest {
+            accounts: account_filters,
+            transactions,
+            commitment: None,
+            ..Default::default()
+        })
+        .await?;
+    let mut stream = client
+        .subscribe(Request::new(ReceiverStream::new(req_rx)))
+        .await?
+        .into_inner();
+    println!("⏺️ [GEYSER_RECORDING] Recording {}", url);
+
+    tokio::pin!(stop);
+    let mut last_flush = tokio::time::Instant::now();
+    loop {
+        tokio::select! {
+            _ = &mut stop => break,
+            msg = stream.message() => match msg? {
+                Some(update) => writer.record(update)?,
+                None => {
+                    println!("⚠️ [GEYSER_RECORDING] Stream closed by the server");
+                    break;
+                }
+            },
+        }
+        if last_flush.elapsed() >= Duration::from_secs(1) {
+            writer.flush()?;
+            last_flush = tokio::time::Instant::now();
+        }
+    }
+    // keep the request side open until here, or the server ends the stream
+    drop(req_tx);
+    writer.flush()?;
+    Ok(())
+}
+
+/* --------------------------------------------------------------------- */
+/*  Replay                                                               */
+/* --------------------------------------------------------------------- */
+
+/// Geyser gRPC service serving a recording to every subscriber.
+#[derive(Clone)]
+pub struct ReplayServer {
+    updates: Arc<Vec<RecordedUpdate>>,
+    /// Pace relative to the recording (2.0 = twice as fast); `None` sends
+    /// as fast as the client reads
+    speed: Option<f64>,
+}
+
+impl ReplayServer {
+    pub fn new(updates: Vec<RecordedUpdate>, speed: Option<f64>) -> Self {
+        Self {
+            updates: Arc::new(updates),
+            speed: speed.filter(|s| *s > 0.0),
+        }
+    }
+
+    /// Serve on `addr` until the process exits.
+    pub async fn serve(self, addr: SocketAddr) -> Result<()> {
+        println!(
+            "⏯️ [GEYSER_REPLAY] Serving {} updates on {} ({})",
+            self.updates.len(),
+            addr,
+            match self.speed {
+                Some(speed) => format!("{}x speed", speed),
+                None => "unthrottled".to_string(),
+            }
+        );
+        Server::builder()
+            .add_service(GeyserServer::new(self))
+            .serve(addr)
+            .await?;
+        Ok(())
+    }
+}
+
+type UpdateStream = Pin<Box<dyn Stream<Item = Result<SubscribeUpdate, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Geyser for ReplayServer {
+    type SubscribeStream = UpdateStream;
+
+    async fn subscribe(
+        &self,
+        request: Request<Streaming<SubscribeRequest>>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        // drain the client's requests (filter updates, pings); they don't
+        // change what a replay sends
+        let mut requests = request.into_inner();
+        tokio::spawn(async move { while let Ok(Some(_)) = requests.message().await {} });
+
+        let updates = self.updates.clone();
+        let speed = self.speed;
+        let (tx, rx) = tokio::sync::mpsc::channel(1024);
+        tokio::spawn(async move {
+            let start = tokio::time::Instant::now();
+            let first_us = updates.first().map_or(0, |u| u.received_at_us);
+            for rec in updates.iter() {
+                if let Some(speed) = speed {
+                    let offset_us = rec.received_at_us.saturating_sub(first_us) as f64 / speed;
+                    tokio::time::sleep_until(start + Duration::from_micros(offset_us as u64)).await;
+                }
+                if let Some(update) = rec.update.clone() {
+                    if tx.send(Ok(update)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            println!(
+                "⏹️ [GEYSER_REPLAY] Replayed {} updates in {:.1}s",
+                updates.len(),
+                start.elapsed().as_secs_f64()
+            );
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    async fn ping(&self, request: Request<PingRequest>) -> Result<Response<PongResponse>, Status> {
+        Ok(Response::new(PongResponse {
+            count: request.into_inner().count,
+        }))
+    }
+
+    async fn get_latest_blockhash(
+        &self,
+        _request: Request<GetLatestBlockhashRequest>,
+    ) -> Result<Response<GetLatestBlockhashResponse>, Status> {
+        Err(Status::unimplemented("not available in a replay"))
+    }
+
+    async fn get_block_height(
+        &self,
+        _request: Request<GetBlockHeightRequest>,
+    ) -> Result<Response<GetBlockHeightResponse>, Status> {
+        Err(Status::unimplemented("not available in a replay"))
+    }
+
+    async fn get_slot(
+        &self,
+        _request: Request<GetSlotRequest>,
+    ) -> Result<Response<GetSlotResponse>, Status> {
+        Err(Status::unimplemented("not available in a replay"))
+    }
+
+    async fn is_blockhash_valid(
+        &self,
+        _request: Request<IsBlockhashValidRequest>,
+    ) -> Result<Response<IsBlockhashValidResponse>, Status> {
+        Err(Status::unimplemented("not available in a replay"))
+    }
+
+    async fn get_version(
+        &self,
+        _request: Request<GetVersionRequest>,
+    ) -> Result<Response<GetVersionResponse>, Status> {
+        Ok(Response::new(GetVersionResponse {
+            version: "geyser-replay".to_string(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::geyser::geyser::{subscribe_update::UpdateOneof, SubscribeUpdatePing};
+
+    #[test]
+    fn test_recording_round_trip() {
+        let path = std::env::temp_dir().join(format!("geyser_rec_{}.bin", std::process::id()));
+        let mut writer = RecordingWriter::create(&path).unwrap();
+        for i in 0..3u64 {
+            writer
+                .write(&RecordedUpdate {
+                    received_at_us: 1_000 + i,
+                    update: Some(SubscribeUpdate {
+                        filters: vec![format!("f{}", i)],
+                        update_oneof: Some(UpdateOneof::Ping(SubscribeUpdatePing {})),
+                        ..Default::default()
+                    }),
+                })
+                .unwrap();
+        }
+        writer.flush().unwrap();
+        drop(writer);
+
+        let recs = read_recording(&path).unwrap();
+        assert_eq!(recs.len(), 3);
+        assert_eq!(recs[2].received_at_us, 1_002);
+        assert_eq!(recs[1].update.as_ref().unwrap().filters, vec!["f1"]);
+
+        // a torn last record is dropped, the rest survive
+        let mut raw = fs::read(&path).unwrap();
+        raw.truncate(raw.len() - 2);
+        fs::write(&path, raw).unwrap();
+        assert_eq!(read_recording(&path).unwrap().len(), 2);
+        let _ = fs::remove_file(&path);
+    }
+}
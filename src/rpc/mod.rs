@@ -1,3 +1,5 @@
 pub mod geyser;
+pub mod geyser_filter;
 pub mod geyser_listener;
+pub mod geyser_recording;
 pub mod solana_storage;
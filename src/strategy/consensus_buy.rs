@@ -0,0 +1,277 @@
+use super::*;
+use crate::config::settings::{ScaleInConfig, Settings};
+use crate::strategy::filters;
+use crate::strategy::follow_buy::{scale_in_for, sized_buy_sol};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Buy a mint only once several tracked wallets have bought it within a short
+/// window. Each wallet contributes its `consensus_weight` (default 1.0) once;
+/// the buy fires when the summed score reaches `min_score`, and is sized at
+/// the mean copy size (see `follow_buy::sized_buy_sol`) of the contributing
+/// wallets scaled by `score / min_score` (capped at `max_scale`). A mint we
+/// already hold goes through `scale_in` like a repeat `follow_buy`.
+///
+/// Use it instead of `follow_buy`, not alongside it.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsensusBuy {
+    /// Weighted score needed to buy; with default weights this is the
+    /// number of distinct wallets
+    pub min_score: f64,
+    /// Signals older than this are dropped
+    pub window_ms: u64,
+    /// Optional slot window, applied on top of `window_ms` for fills that
+    /// carry a slot
+    pub window_slots: Option<u64>,
+    /// Upper bound on the size multiplier
+    pub max_scale: f64,
+    /// Fixed base size; unset means the mean of the contributing wallets
+    pub base_buy_sol: Option<f64>,
+
+    #[serde(skip)]
+    signals: HashMap<Pubkey, Vec<Signal>>,
+    /// Mints already bought, kept until their window lapses so late joiners
+    /// don't trigger a second buy
+    #[serde(skip)]
+    bought: HashMap<Pubkey, Instant>,
+}
+
+#[derive(Debug, Clone)]
+struct Signal {
+    label: String,
+    weight: f64,
+    buy_amount_sol: f64,
+    slot: u64,
+    at: Instant,
+}
+
+impl Default for ConsensusBuy {
+    fn default() -> Self {
+        Self {
+            min_score: 2.0,
+            window_ms: 30_000,
+            window_slots: None,
+            max_scale: 3.0,
+            base_buy_sol: None,
+            signals: HashMap::new(),
+            bought: HashMap::new(),
+        }
+    }
+}
+
+impl ConsensusBuy {
+    fn window(&self) -> Duration {
+        Duration::from_millis(self.window_ms)
+    }
+
+    /// Drop signals that fell out of the time window.
+    fn prune(&mut self, now: Instant) {
+        let window = self.window();
+        self.signals.retain(|_, sigs| {
+            sigs.retain(|s| now.duration_since(s.at) <= window);
+            !sigs.is_empty()
+        });
+        self.bought.retain(|_, at| now.duration_since(*at) <= window);
+    }
+
+    /// Record `sig` for `mint` and return the current weighted score. A
+    /// wallet counts once; a repeat buy refreshes its signal.
+    fn record(&mut self, mint: Pubkey, sig: Signal) -> f64 {
+        let window_slots = self.window_slots;
+        let sigs = self.signals.entry(mint).or_default();
+        sigs.retain(|s| s.label != sig.label);
+        if let Some(max_slots) = window_slots {
+            if sig.slot > 0 {
+                sigs.retain(|s| s.slot == 0 || sig.slot.saturating_sub(s.slot) <= max_slots);
+            }
+        }
+        sigs.push(sig);
+        sigs.iter().map(|s| s.weight).sum()
+    }
+
+    /// `on_fill` with the scale-in lookup passed in, so tests can supply the
+    /// held position.
+    fn on_buy(
+        &mut self,
+        f: &ObservedFill,
+        settings: &Settings,
+        scale_in: impl FnOnce(&ScaleInConfig) -> Result<f64, String>,
+    ) -> Vec<TradePlan> {
+        if f.side != Side::Buy {
+            return Vec::new();
+        }
+        let wallet = match settings.wallet(&f.wallet_label) {
+            Some(w) => w,
+            None => return Vec::new(),
+        };
+
+        // Same per-wallet gate as follow_buy: dust buys are not a signal
+        let gate_lamports = settings.sol_to_lamports(wallet.sol_gate).unwrap_or(u64::MAX);
+        if f.cost_lamports < gate_lamports || wallet.consensus_weight <= 0.0 {
+            return Vec::new();
+        }
+
+        // Auto-disabled wallets don't count towards consensus
+        let buy_amount_sol = match sized_buy_sol(wallet, f, settings) {
+            Some(sol) => sol,
+            None => return Vec::new(),
+        };
+
+        let now = Instant::now();
+        self.prune(now);
+        if self.bought.contains_key(&f.mint) {
+            return Vec::new();
+        }
+
+        let score = self.record(
+            f.mint,
+            Signal {
+                label: f.wallet_label.clone(),
+                weight: wallet.consensus_weight,
+                buy_amount_sol,
+                slot: f.slot,
+                at: now,
+            },
+        );
+        if score < self.min_score {
+            println!(
+                "🤝 [CONSENSUS] {} bought {} → score {:.2}/{:.2}",
+                f.wallet_label, f.mint, score, self.min_score
+            );
+            return Vec::new();
+        }
+
+        if let Err(reason) = filters::check_buy(settings, &f.mint, f.dex)
+            .and_then(|_| filters::check_mint_age(&settings.mint_age, &f.mint, f.slot))
+        {
+            println!("🚫 [CONSENSUS] Skipping {}: {}", f.mint, reason);
+            return Vec::new();
+        }
+
+        let sigs = self.signals.remove(&f.mint).unwrap_or_default();
+        self.bought.insert(f.mint, now);
+
+        // Same scale-in policy as a repeat follow_buy of a held mint
+        let scale_in_cfg = wallet.profile.scale_in.as_ref().unwrap_or(&settings.scale_in);
+        let add_multiplier = match scale_in(scale_in_cfg) {
+            Ok(m) => m,
+            Err(reason) => {
+                println!("⏭️ [CONSENSUS] Not adding to {}: {}", f.mint, reason);
+                return Vec::new();
+            }
+        };
+
+        let base_sol = self.base_buy_sol.unwrap_or_else(|| {
+            sigs.iter().map(|s| s.buy_amount_sol).sum::<f64>() / sigs.len().max(1) as f64
+        });
+        let scale = (score / self.min_score.max(f64::EPSILON)).min(self.max_scale);
+        let buy_sol = base_sol * scale * add_multiplier;
+        let lamports = match settings.sol_to_lamports(buy_sol) {
+            Ok(l) if l > 0 => l,
+            _ => return Vec::new(),
+        };
+
+        let labels: Vec<&str> = sigs.iter().map(|s| s.label.as_str()).collect();
+        println!(
+            "🤝 [CONSENSUS] {} wallets ({}) on {} → score {:.2}, buying {:.4} SOL (x{:.2})",
+            labels.len(),
+            labels.join(", "),
+            f.mint,
+            score,
+            buy_sol,
+            scale * add_multiplier
+        );
+
+        vec![TradePlan::buy(f.dex, f.mint, lamports)]
+    }
+}
+
+impl Strategy for ConsensusBuy {
+    fn on_fill(&mut self, f: &ObservedFill, settings: &Settings) -> Vec<TradePlan> {
+        self.on_buy(f, settings, |cfg| scale_in_for(settings.owner(), f.mint, cfg))
+    }
+
+    fn on_tick(&mut self, _settings: &Settings) -> Vec<TradePlan> {
+        self.prune(Instant::now());
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::settings::test_settings;
+    use crate::positions::{new_position, Position};
+    use crate::strategy::follow_buy::scale_in_held;
+
+    fn signal(label: &str, slot: u64) -> Signal {
+        Signal {
+            label: label.to_string(),
+            weight: 1.0,
+            buy_amount_sol: 0.1,
+            slot,
+            at: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn test_record_counts_distinct_wallets_within_slot_window() {
+        let mut c = ConsensusBuy { window_slots: Some(10), ..Default::default() };
+        let mint = Pubkey::new_unique();
+
+        assert_eq!(c.record(mint, signal("a", 100)), 1.0);
+        // repeat buy from the same wallet does not add weight
+        assert_eq!(c.record(mint, signal("a", 101)), 1.0);
+        assert_eq!(c.record(mint, signal("b", 105)), 2.0);
+        // too many slots after `a` and `b`: they no longer count
+        assert_eq!(c.record(mint, signal("c", 200)), 1.0);
+    }
+
+    #[test]
+    fn test_consensus_on_held_mint_follows_scale_in() {
+        let settings = test_settings(serde_json::json!({
+            "scale_in": { "enabled": true, "max_adds": 1, "add_multiplier": 0.5 },
+            "tracked_wallets": [
+                { "label": "a", "address": Pubkey::new_unique().to_string(),
+                  "sol_gate": 0.0, "buy_amount_sol": 0.1 },
+                { "label": "b", "address": Pubkey::new_unique().to_string(),
+                  "sol_gate": 0.0, "buy_amount_sol": 0.1 }
+            ]
+        }));
+        let mint = Pubkey::new_unique();
+        let mut held = new_position(settings.owner(), mint, Some("consensus_buy"), 0);
+        held.balance = 1_000;
+        held.cost_lamports = 1_000;
+
+        let mut c = ConsensusBuy::default();
+        let trigger = |c: &mut ConsensusBuy, held: &Position| {
+            ["a", "b"]
+                .iter()
+                .flat_map(|label| {
+                    let f = ObservedFill {
+                        mint,
+                        side: Side::Buy,
+                        cost_lamports: 1_000_000,
+                        pct_of_balance: 0.0,
+                        dex: DexKind::PumpSwap,
+                        wallet_label: label.to_string(),
+                        slot: 0,
+                        trader_sol_balance: None,
+                    };
+                    c.on_buy(&f, &settings, |cfg| scale_in_held(Some(held), cfg))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // first consensus on a held mint is the first add: 0.1 SOL × 0.5
+        let plans = trigger(&mut c, &held);
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].buy_lamports, 50_000_000);
+
+        // a new window after that add: `max_adds` reached, no second buy
+        held.adds = 1;
+        c.bought.clear();
+        assert!(trigger(&mut c, &held).is_empty());
+    }
+}
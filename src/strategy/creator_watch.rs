@@ -0,0 +1,120 @@
+use super::*;
+use crate::config::settings::Settings;
+use crate::rpc::geyser_filter;
+use crate::strategy::engine::STRATEGY_ENGINE;
+use crate::utils::pool_tracker;
+use std::collections::{HashMap, HashSet};
+
+/// Name under which the creators are added to the Geyser filter.
+pub const WATCHER: &str = "creator_watch";
+
+/// Exit a position in full, with the emergency sell tip, as soon as the coin
+/// creator of its mint sells or moves tokens of it. The creators of open
+/// positions (from `pool_tracker`) are added to the Geyser subscription on
+/// every tick, so their transactions arrive on the tracked-wallet stream.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CreatorWatch {
+    /// Ignore creator moves below this share of their balance (0.0 – 1.0)
+    pub min_pct_of_balance: f64,
+    /// Also exit on plain transfers (e.g. to a fresh wallet), not only sells
+    pub exit_on_transfer: bool,
+
+    /// mint → creator, for the positions we hold
+    #[serde(skip)]
+    creators: HashMap<Pubkey, Pubkey>,
+    /// Mints already exited, until the position is gone
+    #[serde(skip)]
+    exited: HashSet<Pubkey>,
+}
+
+impl Default for CreatorWatch {
+    fn default() -> Self {
+        Self {
+            min_pct_of_balance: 0.0,
+            exit_on_transfer: true,
+            creators: HashMap::new(),
+            exited: HashSet::new(),
+        }
+    }
+}
+
+impl CreatorWatch {
+    /// Re-read `owner`'s open positions and push their creators to the
+    /// Geyser filter.
+    fn refresh(&mut self, owner: Pubkey) {
+        let engine = match STRATEGY_ENGINE.get() {
+            Some(engine) => engine,
+            None => return,
+        };
+        let held: Vec<Pubkey> = {
+            let pm = engine.positions.lock().unwrap();
+            pm.owned_by(owner)
+                .filter(|p| p.balance > 0)
+                .map(|p| p.mint)
+                .collect()
+        };
+
+        self.creators.retain(|mint, _| held.contains(mint));
+        self.exited.retain(|mint| held.contains(mint));
+        for mint in held {
+            if !self.creators.contains_key(&mint) {
+                if let Some(creator) = pool_tracker::coin_creator(&mint) {
+                    self.creators.insert(mint, creator);
+                }
+            }
+        }
+        geyser_filter::set_watch(WATCHER, self.creators.values().copied().collect());
+    }
+}
+
+impl Strategy for CreatorWatch {
+    fn on_fill(&mut self, _f: &ObservedFill, _settings: &Settings) -> Vec<TradePlan> {
+        Vec::new()
+    }
+
+    fn on_tick(&mut self, settings: &Settings) -> Vec<TradePlan> {
+        self.refresh(settings.owner());
+        Vec::new()
+    }
+
+    fn on_creator_activity(&mut self, ev: &CreatorActivity, settings: &Settings) -> Vec<TradePlan> {
+        if self.creators.get(&ev.mint) != Some(&ev.creator) || self.exited.contains(&ev.mint) {
+            return Vec::new();
+        }
+        if !ev.sold && !self.exit_on_transfer {
+            return Vec::new();
+        }
+        if ev.pct_of_balance < self.min_pct_of_balance {
+            println!(
+                "👀 [CREATOR_WATCH] Creator {} moved {:.2}% of {} (below {:.2}%)",
+                ev.creator,
+                ev.pct_of_balance * 100.0,
+                ev.mint,
+                self.min_pct_of_balance * 100.0
+            );
+            return Vec::new();
+        }
+
+        let dex = match STRATEGY_ENGINE.get().and_then(|engine| {
+            let pm = engine.positions.lock().unwrap();
+            pm.position(settings.owner(), ev.mint)
+                .filter(|p| p.balance > 0)
+                .and_then(|p| p.dex)
+        }) {
+            Some(dex) => dex,
+            None => return Vec::new(),
+        };
+
+        self.exited.insert(ev.mint);
+        println!(
+            "🚨 [CREATOR_WATCH] Creator {} {} {:.2}% of {} (slot {}) → emergency sell 100%",
+            ev.creator,
+            if ev.sold { "sold" } else { "transferred" },
+            ev.pct_of_balance * 100.0,
+            ev.mint,
+            ev.slot
+        );
+        vec![TradePlan::sell_percent(dex, ev.mint, 1.0).urgent()]
+    }
+}
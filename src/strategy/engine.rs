@@ -1,16 +1,24 @@
 //! Fan‑out engine that runs every strategy on each observed fill
 //! and offers shared state ( PositionManager ) to them.
 
-use crate::config::settings::Settings;
+use crate::config::settings::{default_strategies, Settings, StrategyConfig, WalletConfig};
 use once_cell::sync::OnceCell;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
-    positions::PositionManager,
+    paper,
+    positions::{reconcile, PositionManager},
+    utils::pool_tracker,
     strategy::{
-        follow_buy::FollowBuy, follow_sell::FollowSell, take_profit::TakeProfit, ObservedFill,
+        merge,
+        registry,
+        risk::RiskManager,
         /* trait & helper types */
-        Strategy, TradePlan, DexKind,
+        AccountUpdate, CreatorActivity, ObservedFill, Strategy, TradePlan,
     },
 };
 
@@ -35,42 +43,214 @@ impl EngineShared {
     }
 }
 
+/// Buy-size multiplier of `wallet` from its closed-trade score, or `None`
+/// while wallet scoring has it switched off.
+pub fn wallet_size_multiplier(wallet: &WalletConfig, settings: &Settings) -> Option<f64> {
+    let cfg = &settings.wallet_scoring;
+    if !cfg.enabled {
+        return Some(1.0);
+    }
+    let engine = match STRATEGY_ENGINE.get() {
+        Some(engine) => engine,
+        None => return Some(1.0),
+    };
+    let pm = engine.positions.lock().unwrap();
+    let board = pm.wallet_stats();
+    if let Some(reason) = board.disabled_reason(&wallet.label, cfg) {
+        println!("🚫 [WALLET_SCORE] {} auto-disabled: {}", wallet.label, reason);
+        return None;
+    }
+    Some(board.size_multiplier(&wallet.label, cfg))
+}
+
 /* ──────────────────────────────────────────────────────────────────── */
 /*  The engine proper                                                  */
 /* ──────────────────────────────────────────────────────────────────── */
 
+/// A timer exit stays quiet for this long after firing, so the same mint is
+/// not re-sold on every tick while the first sell is still landing.
+const TICK_EXIT_COOLDOWN: Duration = Duration::from_secs(5);
+
 pub struct StrategyEngine {
     strategies: Vec<Box<dyn Strategy + Send>>,
-    /// Keep an `Arc` around so the caller can still access the same
+    /// Registry names, parallel to `strategies`
+    names: Vec<String>,
+    /// Keep an `Arc` around so the caller can still access the same
     /// PositionManager after constructing the engine.
     pub positions: Arc<EngineShared>,
+    /// mint → last time a tick produced a plan for it
+    tick_cooldowns: HashMap<Pubkey, Instant>,
+    /// Portfolio limits every plan passes before it leaves the engine
+    risk: RiskManager,
 }
 
 impl StrategyEngine {
-    /// Create a new engine and register every active strategy exactly once.
+    /// Create a new engine running the default strategy set.
     pub fn new(shared: Arc<EngineShared>) -> Self {
+        Self::from_configs(shared, &default_strategies())
+            .expect("built-in strategies must always build")
+    }
+
+    /// Create an engine from the `strategies` section of the settings file.
+    /// Disabled entries are skipped; unknown names or bad params are an error.
+    pub fn from_settings(shared: Arc<EngineShared>, settings: &Settings) -> anyhow::Result<Self> {
+        Self::from_configs(shared, &settings.strategies)
+    }
+
+    /// Start-up for the bot's entry point: load the position book for the
+    /// configured mode (see `paper::position_manager`), build the engine from
+    /// `settings` and reconcile the book against the chain. Call it before
+    /// the listener starts.
+    pub fn start(settings: &Settings) -> anyhow::Result<Self> {
+        let shared = Arc::new(EngineShared::new(paper::position_manager(settings)?));
+        let engine = Self::from_settings(shared, settings)?;
+        if let Err(e) = reconcile::reconcile(settings, &engine.positions.positions, false) {
+            println!("⚠️ [ENGINE] Start-up reconciliation failed: {}", e);
+        }
+        Ok(engine)
+    }
+
+    fn from_configs(shared: Arc<EngineShared>, configs: &[StrategyConfig]) -> anyhow::Result<Self> {
         /* make the shared handle globally available */
         let _ = STRATEGY_ENGINE.set(shared.clone());
 
-        /* -------- register strategies here -------- */
         let mut strategies: Vec<Box<dyn Strategy + Send>> = Vec::new();
-        strategies.push(Box::new(FollowBuy)); // mirror tracked BUYs 1‑to‑1
-        strategies.push(Box::new(FollowSell)); // mirror tracked SELLs with 90 %‑rule
-        strategies.push(Box::new(TakeProfit)); // auto 50 % take‑profit at +120 % PnL
-                                               /* ------------------------------------------ */
+        let mut names = Vec::new();
+        for cfg in configs.iter().filter(|c| c.enabled) {
+            strategies.push(registry::build(&cfg.name, &cfg.params)?);
+            names.push(cfg.name.clone());
+        }
+        println!("🧠 [ENGINE] Active strategies: {}", names.join(" → "));
 
-        Self {
+        Ok(Self {
             strategies,
+            names,
             positions: shared,
-        }
+            tick_cooldowns: HashMap::new(),
+            risk: RiskManager::new(),
+        })
+    }
+
+    /// Names of the active strategies, in run order.
+    pub fn strategy_names(&self) -> &[String] {
+        &self.names
     }
 
     /// Run *every* strategy on the incoming fill and collect all plans.
+    /// Plans are attributed to the fill's wallet unless a strategy already did,
+    /// merged per mint and side, then screened by the risk manager.
     pub fn on_fill(&mut self, fill: &ObservedFill, settings: &Settings) -> Vec<TradePlan> {
         let mut out = Vec::new();
-        for strat in &mut self.strategies {
-            out.extend(strat.on_fill(fill, settings));
+        for (strat, name) in self.strategies.iter_mut().zip(&self.names) {
+            for mut plan in strat.on_fill(fill, settings) {
+                if plan.wallet_label.is_none() {
+                    plan.wallet_label = Some(fill.wallet_label.clone());
+                }
+                plan.strategy.get_or_insert_with(|| name.clone());
+                out.push(plan);
+            }
         }
-        out
+
+        // A tracked trade is a sighting of its mint too (for the mint-age
+        // filter), noted after the strategies ran so a mint first seen in this
+        // very fill is not judged against its own sighting.
+        pool_tracker::record_mint_seen(fill.mint, fill.slot, false, &settings.fresh_mint_cache);
+        self.screen(out, settings)
+    }
+
+    /// A mint's create transaction landed at `slot` (see
+    /// `pool_tracker::pumpfun_created_mint`). Its slot is the mint's birth
+    /// for the mint-age filter.
+    pub fn on_mint_created(&mut self, mint: Pubkey, slot: u64, settings: &Settings) {
+        pool_tracker::record_mint_seen(mint, slot, true, &settings.fresh_mint_cache);
     }
+
+    /// Merge competing plans, then drop those that break a portfolio limit.
+    fn screen(&mut self, plans: Vec<TradePlan>, settings: &Settings) -> Vec<TradePlan> {
+        if plans.is_empty() {
+            return plans;
+        }
+        let plans = merge::merge(plans, settings.merge_policy);
+        let pm = self.positions.positions.lock().unwrap();
+        self.risk.filter(plans, settings, &pm)
+    }
+
+    /// Run *every* strategy's timer hook and collect all plans.
+    /// Mints that already produced a tick plan within [`TICK_EXIT_COOLDOWN`]
+    /// are skipped.
+    pub fn on_tick(&mut self, settings: &Settings) -> Vec<TradePlan> {
+        /* price updates are written in batches, on the tick */
+        if let Err(e) = self.positions.positions.lock().unwrap().flush_if_due() {
+            println!("⚠️ [ENGINE] Failed to persist positions: {}", e);
+        }
+
+        let now = Instant::now();
+        self.tick_cooldowns
+            .retain(|_, fired| now.duration_since(*fired) < TICK_EXIT_COOLDOWN);
+
+        let mut out = Vec::new();
+        for (strat, name) in self.strategies.iter_mut().zip(&self.names) {
+            for mut plan in strat.on_tick(settings) {
+                if self.tick_cooldowns.contains_key(&plan.mint) {
+                    continue;
+                }
+                plan.strategy.get_or_insert_with(|| name.clone());
+                out.push(plan);
+            }
+        }
+
+        for plan in &out {
+            self.tick_cooldowns.insert(plan.mint, now);
+        }
+        self.screen(out, settings)
+    }
+
+    /// Run *every* strategy on a coin creator's sell or transfer.
+    pub fn on_creator_activity(&mut self, ev: &CreatorActivity, settings: &Settings) -> Vec<TradePlan> {
+        let mut out = Vec::new();
+        for (strat, name) in self.strategies.iter_mut().zip(&self.names) {
+            for mut plan in strat.on_creator_activity(ev, settings) {
+                plan.strategy.get_or_insert_with(|| name.clone());
+                out.push(plan);
+            }
+        }
+        self.screen(out, settings)
+    }
+
+    /// Run *every* strategy on an account update from the Geyser stream.
+    pub fn on_account(&mut self, update: &AccountUpdate, settings: &Settings) -> Vec<TradePlan> {
+        let mut out = Vec::new();
+        for (strat, name) in self.strategies.iter_mut().zip(&self.names) {
+            for mut plan in strat.on_account(update, settings) {
+                plan.strategy.get_or_insert_with(|| name.clone());
+                out.push(plan);
+            }
+        }
+        self.screen(out, settings)
+    }
+}
+
+/// Start the strategy timer: every `settings.strategy_tick_ms` the engine's
+/// `on_tick` runs and the resulting plans are handed to the execution side
+/// through `plans`. Call this once during bot initialization.
+pub fn spawn_tick_loop(
+    engine: Arc<tokio::sync::Mutex<StrategyEngine>>,
+    settings: Settings,
+    plans: UnboundedSender<TradePlan>,
+) {
+    let period = Duration::from_millis(settings.strategy_tick_ms.max(50));
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            let tick_plans = engine.lock().await.on_tick(&settings);
+            for plan in tick_plans {
+                if plans.send(plan).is_err() {
+                    println!("⚠️ [ENGINE] Plan receiver dropped – stopping strategy timer");
+                    return;
+                }
+            }
+        }
+    });
+    println!("⏱️ [ENGINE] Started strategy timer ({}ms interval)", period.as_millis());
 }
@@ -0,0 +1,364 @@
+//! Buy‑path filters: mint / creator / venue allow and deny lists, and the
+//! minimum mint age.
+//!
+//! The lists live in the JSON file named by `filters_file` in the settings
+//! (relative to `workdir`) and are re‑read whenever that file changes, so
+//! they can be edited while the bot runs. Mints closed at a loss can be
+//! denied automatically; those are kept in `config/auto_deny.json` under the
+//! workdir so they survive restarts.
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::config::settings::{MintAgeConfig, Settings};
+use crate::positions::{state_path, unix_timestamp, write_atomic};
+use crate::strategy::DexKind;
+use crate::utils::pool_tracker;
+
+/// Mints denied after losing trades (managed by the bot, not by hand), in
+/// `AUTO_DENY_DIR` under the workdir.
+const AUTO_DENY_FILE: &str = "auto_deny.json";
+const AUTO_DENY_DIR: &str = "config";
+/// Where the list was kept before it moved under the workdir
+const AUTO_DENY_LEGACY_DIR: &str = "src/config";
+
+/// How often the filters file's mtime is checked.
+const RELOAD_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/* --------------------------------------------------------------------- */
+/*  File format                                                          */
+/* --------------------------------------------------------------------- */
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FilterFile {
+    /// Only buy mints/creators on the allow lists
+    allow_only: bool,
+    deny_mints: Vec<String>,
+    deny_creators: Vec<String>,
+    deny_dexes: Vec<DexKind>,
+    allow_mints: Vec<String>,
+    allow_creators: Vec<String>,
+    /// When non‑empty, buy only on these venues
+    allow_dexes: Vec<DexKind>,
+    /// Deny mints whose position closed at or below `auto_deny_loss_percent`
+    auto_deny_losers: bool,
+    auto_deny_loss_percent: f64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AutoDenyFile {
+    mints: Vec<String>,
+}
+
+/* --------------------------------------------------------------------- */
+/*  Parsed lists                                                         */
+/* --------------------------------------------------------------------- */
+#[derive(Debug, Default)]
+struct FilterLists {
+    allow_only: bool,
+    deny_mints: HashSet<Pubkey>,
+    deny_creators: HashSet<Pubkey>,
+    deny_dexes: Vec<DexKind>,
+    allow_mints: HashSet<Pubkey>,
+    allow_creators: HashSet<Pubkey>,
+    allow_dexes: Vec<DexKind>,
+    auto_deny_losers: bool,
+    auto_deny_loss_percent: f64,
+}
+
+fn parse_keys(list: &[String], what: &str) -> HashSet<Pubkey> {
+    list.iter()
+        .filter_map(|s| match Pubkey::from_str(s.trim()) {
+            Ok(k) => Some(k),
+            Err(_) => {
+                println!("⚠️ [FILTER] Ignoring invalid {} entry `{}`", what, s);
+                None
+            }
+        })
+        .collect()
+}
+
+impl From<FilterFile> for FilterLists {
+    fn from(f: FilterFile) -> Self {
+        Self {
+            allow_only: f.allow_only,
+            deny_mints: parse_keys(&f.deny_mints, "deny_mints"),
+            deny_creators: parse_keys(&f.deny_creators, "deny_creators"),
+            deny_dexes: f.deny_dexes,
+            allow_mints: parse_keys(&f.allow_mints, "allow_mints"),
+            allow_creators: parse_keys(&f.allow_creators, "allow_creators"),
+            allow_dexes: f.allow_dexes,
+            auto_deny_losers: f.auto_deny_losers,
+            auto_deny_loss_percent: f.auto_deny_loss_percent,
+        }
+    }
+}
+
+impl FilterLists {
+    /// Why a buy of `mint` on `dex` (created by `creator`, if known) is blocked.
+    fn blocked(
+        &self,
+        mint: &Pubkey,
+        creator: Option<&Pubkey>,
+        dex: DexKind,
+        auto_denied: &HashSet<Pubkey>,
+    ) -> Option<String> {
+        if self.deny_mints.contains(mint) {
+            return Some("mint is on deny_mints".into());
+        }
+        if auto_denied.contains(mint) {
+            return Some("mint was auto-denied after a losing trade".into());
+        }
+        if let Some(c) = creator.filter(|c| self.deny_creators.contains(*c)) {
+            return Some(format!("creator {} is on deny_creators", c));
+        }
+        if self.deny_dexes.contains(&dex) {
+            return Some(format!("{:?} is on deny_dexes", dex));
+        }
+        if !self.allow_dexes.is_empty() && !self.allow_dexes.contains(&dex) {
+            return Some(format!("{:?} is not on allow_dexes", dex));
+        }
+        if self.allow_only {
+            let allowed = self.allow_mints.contains(mint)
+                || creator.map_or(false, |c| self.allow_creators.contains(c));
+            if !allowed {
+                return Some("allow-only mode and neither mint nor creator is allowed".into());
+            }
+        }
+        None
+    }
+}
+
+/* --------------------------------------------------------------------- */
+/*  Reloading state                                                      */
+/* --------------------------------------------------------------------- */
+#[derive(Debug, Default)]
+struct TradeFilters {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    checked_at: Option<Instant>,
+    lists: FilterLists,
+    auto_denied: HashSet<Pubkey>,
+    auto_deny_loaded: bool,
+    workdir: Option<PathBuf>,
+    auto_deny_path: Option<PathBuf>,
+}
+
+static FILTERS: Lazy<Mutex<TradeFilters>> = Lazy::new(|| Mutex::new(TradeFilters::default()));
+
+impl TradeFilters {
+    /// Keep the auto‑deny list under `workdir`, reloading it if that moved.
+    fn use_workdir(&mut self, workdir: &Path) {
+        if self.workdir.as_deref() == Some(workdir) {
+            return;
+        }
+        self.workdir = Some(workdir.to_path_buf());
+        let path = state_path(workdir, AUTO_DENY_DIR, AUTO_DENY_LEGACY_DIR, AUTO_DENY_FILE)
+            .unwrap_or_else(|e| {
+                println!("⚠️ [FILTER] Failed to move the auto-deny list: {}", e);
+                workdir.join(AUTO_DENY_DIR).join(AUTO_DENY_FILE)
+            });
+        self.auto_deny_path = Some(path);
+        self.auto_deny_loaded = false;
+    }
+
+    fn ensure_auto_deny(&mut self) {
+        if self.auto_deny_path.is_none() {
+            self.use_workdir(Path::new("."));
+        }
+        if !self.auto_deny_loaded {
+            self.auto_deny_loaded = true;
+            if let Some(path) = &self.auto_deny_path {
+                self.auto_denied = load_auto_deny(path);
+            }
+        }
+    }
+
+    /// Re‑read the filters file if the configured path or its mtime changed.
+    fn refresh(&mut self, settings: &Settings) {
+        self.use_workdir(&settings.workdir);
+        self.ensure_auto_deny();
+
+        let path = settings
+            .filters_file
+            .as_deref()
+            .map(|file| settings.workdir.join(file));
+        if path != self.path {
+            self.path = path;
+            self.modified = None;
+            self.checked_at = None;
+            self.lists = FilterLists::default();
+        }
+        let path = match &self.path {
+            Some(p) => p.clone(),
+            None => return,
+        };
+
+        let now = Instant::now();
+        if self.checked_at.map_or(false, |t| now.duration_since(t) < RELOAD_CHECK_INTERVAL) {
+            return;
+        }
+        self.checked_at = Some(now);
+
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+        if modified.is_some() && modified == self.modified {
+            return;
+        }
+        self.modified = modified;
+
+        match fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|raw| Ok(serde_json::from_str::<FilterFile>(&raw)?))
+        {
+            Ok(file) => {
+                self.lists = file.into();
+                println!(
+                    "🧹 [FILTER] Loaded {}: {} denied mints, {} denied creators, allow-only {}",
+                    path.display(),
+                    self.lists.deny_mints.len(),
+                    self.lists.deny_creators.len(),
+                    self.lists.allow_only
+                );
+            }
+            // keep the previous lists; a half‑saved file should not open the gates
+            Err(e) => println!("⚠️ [FILTER] Failed to load {}: {}", path.display(), e),
+        }
+    }
+}
+
+fn load_auto_deny(path: &Path) -> HashSet<Pubkey> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(_) => return HashSet::new(),
+    };
+    match serde_json::from_str::<AutoDenyFile>(&raw) {
+        Ok(file) => parse_keys(&file.mints, "auto_deny"),
+        Err(e) => {
+            println!("⚠️ [FILTER] Failed to parse {}: {}", path.display(), e);
+            HashSet::new()
+        }
+    }
+}
+
+fn persist_auto_deny(path: &Path, mints: &HashSet<Pubkey>) -> std::io::Result<()> {
+    let mut list: Vec<String> = mints.iter().map(|m| m.to_string()).collect();
+    list.sort();
+    write_atomic(path, &serde_json::to_vec_pretty(&AutoDenyFile { mints: list })?)
+}
+
+/* --------------------------------------------------------------------- */
+/*  Public API                                                           */
+/* --------------------------------------------------------------------- */
+
+/// `Err(reason)` when the lists forbid buying `mint` on `dex`.
+pub fn check_buy(settings: &Settings, mint: &Pubkey, dex: DexKind) -> Result<(), String> {
+    let mut filters = FILTERS.lock().unwrap();
+    filters.refresh(settings);
+
+    let creator = pool_tracker::coin_creator(mint);
+    match filters
+        .lists
+        .blocked(mint, creator.as_ref(), dex, &filters.auto_denied)
+    {
+        Some(reason) => Err(reason),
+        None => Ok(()),
+    }
+}
+
+/// `Err(reason)` when `mint` is younger than `cfg` allows. `slot` is the slot
+/// of the fill being copied (0 if unknown).
+pub fn check_mint_age(cfg: &MintAgeConfig, mint: &Pubkey, slot: u64) -> Result<(), String> {
+    if cfg.min_slots.is_none() && cfg.min_seconds.is_none() {
+        return Ok(());
+    }
+    let seen = match pool_tracker::mint_first_seen(mint) {
+        Some(seen) => seen,
+        None if cfg.reject_unknown => return Err("mint age unknown".into()),
+        None => return Ok(()),
+    };
+
+    let age_secs = unix_timestamp().saturating_sub(seen.seen_at);
+    let age_slots = (slot > 0 && seen.slot > 0).then(|| slot.saturating_sub(seen.slot));
+
+    let young_by_slots = matches!((cfg.min_slots, age_slots), (Some(min), Some(age)) if age < min);
+    let young_by_secs = cfg.min_seconds.map_or(false, |min| age_secs < min);
+    if !young_by_slots && !young_by_secs {
+        return Ok(());
+    }
+
+    // Without the create tx a recent first sighting only means we hadn't
+    // noticed the mint before, not that it is new.
+    if !seen.from_create && !cfg.reject_unknown {
+        return Ok(());
+    }
+    Err(format!(
+        "mint too young ({}s, {} slots since {})",
+        age_secs,
+        age_slots.map_or("?".to_string(), |s| s.to_string()),
+        if seen.from_create { "create" } else { "first sighting" }
+    ))
+}
+
+/// Keep the auto‑deny list under `workdir` (`Settings::workdir`). The live
+/// book calls this before it reports closes; `check_buy` does it too.
+pub fn use_workdir(workdir: &Path) {
+    FILTERS.lock().unwrap().use_workdir(workdir);
+}
+
+/// Called when a position is closed in full; denies the mint from now on
+/// if auto‑deny is enabled and the trade lost at least the configured %.
+pub fn note_closed_trade(mint: Pubkey, return_pct: f64) {
+    let mut filters = FILTERS.lock().unwrap();
+    filters.ensure_auto_deny();
+    if !filters.lists.auto_deny_losers || return_pct > filters.lists.auto_deny_loss_percent {
+        return;
+    }
+    if filters.auto_denied.insert(mint) {
+        println!(
+            "🚫 [FILTER] Auto-denied {} after closing at {:.1}%",
+            mint, return_pct
+        );
+        if let Some(path) = &filters.auto_deny_path {
+            if let Err(e) = persist_auto_deny(path, &filters.auto_denied) {
+                println!("⚠️ [FILTER] Failed to save {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocked() {
+        let mint = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let none = HashSet::new();
+
+        let mut lists = FilterLists {
+            deny_creators: [creator].into_iter().collect(),
+            ..Default::default()
+        };
+        assert!(lists.blocked(&mint, None, DexKind::Pumpfun, &none).is_none());
+        assert!(lists.blocked(&mint, Some(&creator), DexKind::Pumpfun, &none).is_some());
+
+        lists.deny_creators.clear();
+        lists.allow_only = true;
+        assert!(lists.blocked(&mint, Some(&creator), DexKind::Pumpfun, &none).is_some());
+        lists.allow_creators.insert(creator);
+        assert!(lists.blocked(&mint, Some(&creator), DexKind::Pumpfun, &none).is_none());
+
+        lists.allow_dexes = vec![DexKind::PumpSwap];
+        assert!(lists.blocked(&mint, Some(&creator), DexKind::Pumpfun, &none).is_some());
+    }
+}
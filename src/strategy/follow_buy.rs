@@ -1,8 +1,104 @@
 use super::*;
-use crate::config::settings::Settings;
+use crate::config::settings::{BuySizing, ScaleInConfig, Settings, SizingMode, WalletConfig};
+use crate::positions::Position;
+use crate::strategy::engine::{wallet_size_multiplier, STRATEGY_ENGINE};
+use crate::strategy::filters;
 
 pub struct FollowBuy;
 
+/// Our buy in SOL for a tracked buy of `spend_sol`, before the min/max clamps.
+fn raw_buy_sol(
+    sizing: &BuySizing,
+    buy_amount_sol: f64,
+    spend_sol: f64,
+    trader_balance_sol: Option<f64>,
+) -> f64 {
+    match &sizing.mode {
+        SizingMode::Fixed => buy_amount_sol,
+        SizingMode::Ratio { ratio } => spend_sol * ratio,
+        SizingMode::Tiers { tiers } => tiers
+            .iter()
+            .filter(|t| spend_sol >= t.min_spend_sol)
+            .max_by(|a, b| a.min_spend_sol.total_cmp(&b.min_spend_sol))
+            .map_or(0.0, |t| t.buy_sol),
+        SizingMode::BalanceShare { multiplier } => match trader_balance_sol {
+            Some(balance) if balance > 0.0 => {
+                buy_amount_sol * multiplier * (spend_sol / balance).min(1.0)
+            }
+            _ => buy_amount_sol,
+        },
+    }
+}
+
+/// Size multiplier for another buy into `pos`, or why the add is skipped.
+fn scale_in_multiplier(pos: &Position, cfg: &ScaleInConfig) -> Result<f64, String> {
+    if pos.adds >= cfg.max_adds {
+        return Err(format!("already added {} of {} times", pos.adds, cfg.max_adds));
+    }
+    let needs_price = cfg.only_in_profit || cfg.min_pnl_percent.is_some() || cfg.max_pnl_percent.is_some();
+    if needs_price {
+        // PnL vs. `Position::avg_cost`
+        let pnl = pos
+            .unrealised_pnl_pct()
+            .ok_or_else(|| "no price yet to compare with our average cost".to_string())?;
+        if cfg.only_in_profit && pnl <= 0.0 {
+            return Err(format!("position not in profit ({:.1}%)", pnl));
+        }
+        if cfg.min_pnl_percent.map_or(false, |min| pnl < min)
+            || cfg.max_pnl_percent.map_or(false, |max| pnl > max)
+        {
+            return Err(format!("PnL {:.1}% outside the scale-in band", pnl));
+        }
+    }
+    Ok(cfg.add_multiplier.powi(pos.adds as i32 + 1))
+}
+
+/// Scale-in multiplier for buying more of `held` (1.0 when nothing is held).
+pub(crate) fn scale_in_held(held: Option<&Position>, cfg: &ScaleInConfig) -> Result<f64, String> {
+    match held.filter(|p| cfg.enabled && p.balance > 0) {
+        Some(pos) => scale_in_multiplier(pos, cfg),
+        None => Ok(1.0),
+    }
+}
+
+/// Scale-in multiplier for `owner` copying a buy of `mint` (1.0 for a first
+/// entry).
+pub fn scale_in_for(owner: Pubkey, mint: Pubkey, cfg: &ScaleInConfig) -> Result<f64, String> {
+    if !cfg.enabled {
+        return Ok(1.0);
+    }
+    let engine = match STRATEGY_ENGINE.get() {
+        Some(engine) => engine,
+        None => return Ok(1.0),
+    };
+    let pm = engine.positions.lock().unwrap();
+    scale_in_held(pm.position(owner, mint), cfg)
+}
+
+/// SOL to spend copying `f` from `wallet`: the wallet's sizing mode (profile
+/// override, else global), weighted by its score and clamped. `None` means
+/// don't copy (wallet auto-disabled or nothing to buy).
+pub fn sized_buy_sol(wallet: &WalletConfig, f: &ObservedFill, settings: &Settings) -> Option<f64> {
+    let weight = wallet_size_multiplier(wallet, settings)?;
+    let sizing = wallet.profile.buy_sizing.as_ref().unwrap_or(&settings.buy_sizing);
+
+    let spend_sol = f.cost_lamports as f64 / 1_000_000_000.0;
+    let balance_sol = f.trader_sol_balance.map(|l| l as f64 / 1_000_000_000.0);
+    let raw = raw_buy_sol(sizing, wallet.buy_amount_sol, spend_sol, balance_sol) * weight;
+    if raw <= 0.0 {
+        return None;
+    }
+
+    let mut sol = raw;
+    if let Some(min) = sizing.min_buy_sol {
+        sol = sol.max(min);
+    }
+    if let Some(max) = sizing.max_buy_sol {
+        sol = sol.min(max);
+    }
+    Some(sol)
+}
+
 impl Strategy for FollowBuy {
     fn on_fill(&mut self, f: &ObservedFill, settings: &Settings) -> Vec<TradePlan> {
         // Only follow buy transactions
@@ -33,9 +129,32 @@ impl Strategy for FollowBuy {
             return Vec::new();
         }
 
-        // Use per-wallet buy amount
+        // Mint / creator / venue allow & deny lists, then minimum mint age
+        let mint_age = wallet_config.profile.mint_age.as_ref().unwrap_or(&settings.mint_age);
+        if let Err(reason) = filters::check_buy(settings, &f.mint, f.dex)
+            .and_then(|_| filters::check_mint_age(mint_age, &f.mint, f.slot))
+        {
+            println!("🚫 [FOLLOW_BUY] Skipping {}: {}", f.mint, reason);
+            return Vec::new();
+        }
+
+        // Repeat buys of a held mint follow the scale-in policy
+        let scale_in = wallet_config.profile.scale_in.as_ref().unwrap_or(&settings.scale_in);
+        let add_multiplier = match scale_in_for(settings.owner(), f.mint, scale_in) {
+            Ok(m) => m,
+            Err(reason) => {
+                println!("⏭️ [FOLLOW_BUY] Not adding to {}: {}", f.mint, reason);
+                return Vec::new();
+            }
+        };
+
+        // Size relative to the tracked trade, weighted by the wallet's track record
+        let buy_amount_sol = match sized_buy_sol(wallet_config, f, settings) {
+            Some(sol) => sol * add_multiplier,
+            None => return Vec::new(),
+        };
         let lamports = settings
-            .sol_to_lamports(wallet_config.buy_amount_sol)
+            .sol_to_lamports(buy_amount_sol)
             .unwrap_or_else(|_| 0);
 
         // Create appropriate trade plan based on the DEX
@@ -49,3 +168,62 @@ impl Strategy for FollowBuy {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::settings::SizingTier;
+    use crate::positions::new_position;
+
+    #[test]
+    fn test_scale_in_multiplier() {
+        let cfg = ScaleInConfig {
+            enabled: true,
+            max_adds: 2,
+            add_multiplier: 0.5,
+            only_in_profit: true,
+            ..Default::default()
+        };
+        let mut pos = new_position(Pubkey::new_unique(), Pubkey::new_unique(), None, 0);
+        pos.balance = 1_000;
+        pos.cost_lamports = 1_000; // avg cost 1.0
+
+        assert!(scale_in_multiplier(&pos, &cfg).is_err()); // no price yet
+        pos.last_price = Some(1.2);
+        assert_eq!(scale_in_multiplier(&pos, &cfg), Ok(0.5));
+        pos.adds = 1;
+        assert_eq!(scale_in_multiplier(&pos, &cfg), Ok(0.25));
+        pos.adds = 2;
+        assert!(scale_in_multiplier(&pos, &cfg).is_err());
+        pos.adds = 0;
+        pos.last_price = Some(0.9);
+        assert!(scale_in_multiplier(&pos, &cfg).is_err());
+    }
+
+    #[test]
+    fn test_raw_buy_sol_modes() {
+        let tiers = BuySizing {
+            mode: SizingMode::Tiers {
+                tiers: vec![
+                    SizingTier { min_spend_sol: 5.0, buy_sol: 0.5 },
+                    SizingTier { min_spend_sol: 1.0, buy_sol: 0.1 },
+                ],
+            },
+            ..Default::default()
+        };
+        assert_eq!(raw_buy_sol(&tiers, 0.2, 0.5, None), 0.0);
+        assert_eq!(raw_buy_sol(&tiers, 0.2, 2.0, None), 0.1);
+        assert_eq!(raw_buy_sol(&tiers, 0.2, 7.0, None), 0.5);
+
+        let ratio = BuySizing { mode: SizingMode::Ratio { ratio: 0.1 }, ..Default::default() };
+        assert!((raw_buy_sol(&ratio, 0.2, 5.0, None) - 0.5).abs() < 1e-12);
+
+        let share = BuySizing {
+            mode: SizingMode::BalanceShare { multiplier: 2.0 },
+            ..Default::default()
+        };
+        // spent a quarter of the balance → 2 × 0.2 × 0.25
+        assert!((raw_buy_sol(&share, 0.2, 5.0, Some(20.0)) - 0.1).abs() < 1e-12);
+        assert_eq!(raw_buy_sol(&share, 0.2, 5.0, None), 0.2);
+    }
+}
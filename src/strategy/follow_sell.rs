@@ -1,28 +1,91 @@
 use super::*;
 use crate::config::settings::Settings;
+use crate::positions::holdings;
+use crate::strategy::engine::STRATEGY_ENGINE;
 
-pub struct FollowSell;
+/// Params from the `strategies` settings section.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FollowSell {
+    /// Tracked sells of at least this share of their balance become a 100 % sell
+    pub full_exit_at: f64,
+}
+
+impl Default for FollowSell {
+    fn default() -> Self {
+        Self { full_exit_at: 0.90 }
+    }
+}
+
+impl FollowSell {
+    /// Our sell fraction for `f`'s wallet selling `tracked_pct` of its bag,
+    /// or `None` if that wallet has no stake in our position. Positions
+    /// without attribution mirror any wallet as before.
+    fn attributed_pct(&self, f: &ObservedFill, tracked_pct: f64, owner: Pubkey) -> Option<f64> {
+        let engine = match STRATEGY_ENGINE.get() {
+            Some(engine) => engine,
+            None => return Some(tracked_pct),
+        };
+        let pm = engine.positions.lock().unwrap();
+        let share = match pm
+            .position(owner, f.mint)
+            .and_then(|p| p.source_share(&f.wallet_label))
+        {
+            Some(share) => share,
+            None => return Some(tracked_pct),
+        };
+        if share <= 0.0 {
+            println!(
+                "⏭️ [FOLLOW_SELL] Ignoring {} selling {}: not a source of our position",
+                f.wallet_label, f.mint
+            );
+            return None;
+        }
+
+        // the wallet's stake shrinks once the sell is booked
+        // (`PositionManager::record_sell`)
+        Some((tracked_pct * share).min(1.0))
+    }
+}
 
 impl Strategy for FollowSell {
-    fn on_fill(&mut self, f: &ObservedFill, _settings: &Settings) -> Vec<TradePlan> {
+    fn on_fill(&mut self, f: &ObservedFill, settings: &Settings) -> Vec<TradePlan> {
         // Only process SELL events
         if f.side != Side::Sell {
             return Vec::new();
         }
         
-        // Debug the percentage values
-        
-        let pct = if f.pct_of_balance >= 0.90 {
-            println!("✅ [FOLLOW_SELL] Tracked wallet sold >90% → Bot will sell 100%");
+        // Only mirror sells of mints we hold (pending buys haven't landed yet)
+        let owner = settings.owner();
+        if !holdings::is_held(&owner, &f.mint) {
+            println!(
+                "⏭️ [FOLLOW_SELL] Ignoring {} selling {}: not held",
+                f.wallet_label, f.mint
+            );
+            return Vec::new();
+        }
+
+        let tracked_pct = if f.pct_of_balance >= self.full_exit_at {
             1.0
         } else {
-            println!("✅ [FOLLOW_SELL] Tracked wallet sold {:.2}% → Bot will sell {:.2}%", 
-                f.pct_of_balance * 100.0, f.pct_of_balance * 100.0);
             f.pct_of_balance
         };
+
+        // Only mirror wallets that got us into this token, weighted by their
+        // share of our position
+        let pct = match self.attributed_pct(f, tracked_pct, owner) {
+            Some(pct) => pct,
+            None => return Vec::new(),
+        };
+        println!(
+            "✅ [FOLLOW_SELL] {} sold {:.2}% → Bot will sell {:.2}%",
+            f.wallet_label,
+            tracked_pct * 100.0,
+            pct * 100.0
+        );
         
         // Create appropriate TradePlan based on DEX type
-        match f.dex {
+        let mut plans = match f.dex {
             DexKind::Pumpfun => {
                 vec![TradePlan::sell_pumpfun_percent(f.mint, pct)]
             }
@@ -46,6 +109,11 @@ impl Strategy for FollowSell {
                 println!("🚀 [FOLLOW_SELL] Creating Raydium Launchpad sell plan for {:.2}%", pct * 100.0);
                 vec![TradePlan::sell_raydium_launchpad_percent(f.mint, pct)]
             }
+        };
+        // released from the wallet's stake once booked, even if merged
+        for plan in &mut plans {
+            plan.follow_sell_pct = Some(pct);
         }
+        plans
     }
 }
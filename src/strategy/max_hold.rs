@@ -0,0 +1,58 @@
+use super::*;
+use crate::config::settings::Settings;
+use crate::positions::unix_timestamp;
+use crate::strategy::engine::STRATEGY_ENGINE;
+
+/// Timer-only exits: positions held too long, or gone quiet (no fill and no
+/// price update), are closed in full. Unset params fall back to the global
+/// `max_hold_seconds` / `stale_position_seconds` settings.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MaxHold {
+    pub max_hold_seconds: Option<u64>,
+    pub stale_position_seconds: Option<u64>,
+}
+
+impl Strategy for MaxHold {
+    fn on_fill(&mut self, _f: &ObservedFill, _settings: &Settings) -> Vec<TradePlan> {
+        Vec::new()
+    }
+
+    fn on_tick(&mut self, settings: &Settings) -> Vec<TradePlan> {
+        let max_hold = self.max_hold_seconds.or(settings.max_hold_seconds);
+        let stale_after = self.stale_position_seconds.or(settings.stale_position_seconds);
+        if max_hold.is_none() && stale_after.is_none() {
+            return Vec::new();
+        }
+
+        let now = unix_timestamp();
+        let mut out = Vec::new();
+
+        if let Some(engine) = STRATEGY_ENGINE.get() {
+            let pm = engine.positions.lock().unwrap();
+            for pos in pm.owned_by(settings.owner()) {
+                let dex = match pos.dex {
+                    Some(dex) if pos.balance > 0 => dex,
+                    _ => continue,
+                };
+
+                if let (Some(max), Some(held)) = (max_hold, pos.held_secs(now)) {
+                    if held >= max {
+                        println!("⏰ [MAX_HOLD] {} held for {}s (max {}s) → selling 100%", pos.mint, held, max);
+                        out.push(TradePlan::sell_percent(dex, pos.mint, 1.0));
+                        continue;
+                    }
+                }
+
+                if let Some(stale) = stale_after {
+                    let idle = now.saturating_sub(pos.updated_at);
+                    if idle >= stale {
+                        println!("🧹 [MAX_HOLD] {} idle for {}s (stale after {}s) → selling 100%", pos.mint, idle, stale);
+                        out.push(TradePlan::sell_percent(dex, pos.mint, 1.0));
+                    }
+                }
+            }
+        }
+        out
+    }
+}
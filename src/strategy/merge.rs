@@ -0,0 +1,170 @@
+//! Reduce the plans of one fill (or tick) to at most one per mint and side.
+//!
+//! Several strategies can react to the same event – a tracked sell may make
+//! `follow_sell` exit 100 % while `take_profit` sells 50 % – and sending both
+//! only gets the second transaction rejected. Plans arrive in strategy order
+//! (the order of the `strategies` section), which is what `priority` uses.
+
+use std::collections::HashMap;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::config::settings::MergePolicy;
+use crate::strategy::{Side, TradePlan};
+
+/// Merge `plans` under `policy`. The surviving plan's `strategy` names the
+/// winner (`a+b` when sells were summed); sell fractions never exceed 100 %.
+pub fn merge(plans: Vec<TradePlan>, policy: MergePolicy) -> Vec<TradePlan> {
+    if plans.len() < 2 {
+        return plans.into_iter().map(cap_sell).collect();
+    }
+
+    // group by (mint, side), keeping first‑appearance order of the groups
+    let mut order: Vec<(Pubkey, Side)> = Vec::new();
+    let mut groups: HashMap<(Pubkey, Side), Vec<TradePlan>> = HashMap::new();
+    for plan in plans {
+        let key = (plan.mint, plan.side);
+        if !groups.contains_key(&key) {
+            order.push(key);
+        }
+        groups.entry(key).or_default().push(plan);
+    }
+
+    order
+        .into_iter()
+        .filter_map(|key| groups.remove(&key))
+        .map(|group| cap_sell(merge_group(group, policy)))
+        .collect()
+}
+
+fn merge_group(mut group: Vec<TradePlan>, policy: MergePolicy) -> TradePlan {
+    if group.len() == 1 {
+        return group.pop().unwrap();
+    }
+    let side = group[0].side;
+    let names: Vec<String> = group.iter().map(strategy_name).collect();
+    // an emergency exit stays urgent whichever plan wins
+    let urgent = group.iter().any(|p| p.urgent);
+
+    let mut winner = match (policy, side) {
+        (MergePolicy::Sum, Side::Sell) => {
+            let total: f64 = group.iter().map(|p| p.sell_pct.unwrap_or(0.0)).sum();
+            let rungs: Vec<usize> = group
+                .iter()
+                .flat_map(|p| p.take_profit_rungs.iter().copied())
+                .collect();
+            // the mirrored part and whose sell it mirrored, for `book_sell`
+            let mirrored = group
+                .iter()
+                .find(|p| p.follow_sell_pct.is_some())
+                .map(|p| (p.follow_sell_pct, p.wallet_label.clone()));
+            let mut plan = group.swap_remove(0);
+            plan.sell_pct = Some(total);
+            plan.take_profit_rungs = rungs;
+            if let Some((pct, label)) = mirrored {
+                plan.follow_sell_pct = pct;
+                plan.wallet_label = label;
+            }
+            // a summed sell no longer matches any single token amount
+            plan.known_token_amount = None;
+            plan.strategy = Some(names.join("+"));
+            plan.urgent = urgent;
+            println!(
+                "🔀 [MERGE] {} SELL: summed {} → {:.1}%",
+                plan.mint,
+                names.join(", "),
+                total.min(1.0) * 100.0
+            );
+            return plan;
+        }
+        (MergePolicy::Priority, Side::Sell) => {
+            // a risk exit is never replaced by a smaller discretionary sell
+            let idx = group.iter().position(is_risk_exit).unwrap_or(0);
+            group.swap_remove(idx)
+        }
+        (MergePolicy::Largest, _) => {
+            let idx = (0..group.len())
+                .max_by(|&a, &b| size(&group[a]).total_cmp(&size(&group[b])).then(b.cmp(&a)))
+                .unwrap_or(0);
+            group.swap_remove(idx)
+        }
+        _ => group.swap_remove(0),
+    };
+    winner.urgent = urgent;
+
+    let won = strategy_name(&winner);
+    let lost: Vec<&String> = names.iter().filter(|n| **n != won).collect();
+    println!(
+        "🔀 [MERGE] {} {:?}: {} won over {:?} ({:?})",
+        winner.mint, side, won, lost, policy
+    );
+    winner
+}
+
+fn strategy_name(plan: &TradePlan) -> String {
+    plan.strategy.clone().unwrap_or_else(|| "?".to_string())
+}
+
+/// Exits that protect the position: stops and urgent (emergency) sells.
+fn is_risk_exit(plan: &TradePlan) -> bool {
+    plan.urgent
+        || matches!(
+            plan.strategy.as_deref(),
+            Some("stop_loss") | Some("trailing_stop")
+        )
+}
+
+/// Comparable size of a plan: sell fraction or buy lamports.
+fn size(plan: &TradePlan) -> f64 {
+    match plan.side {
+        Side::Sell => plan.sell_pct.unwrap_or(0.0),
+        Side::Buy => plan.buy_lamports as f64,
+    }
+}
+
+fn cap_sell(mut plan: TradePlan) -> TradePlan {
+    if let Some(pct) = plan.sell_pct {
+        plan.sell_pct = Some(pct.clamp(0.0, 1.0));
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::DexKind;
+
+    fn sell(mint: Pubkey, pct: f64, strategy: &str) -> TradePlan {
+        let mut plan = TradePlan::sell_percent(DexKind::Pumpfun, mint, pct);
+        plan.strategy = Some(strategy.to_string());
+        plan
+    }
+
+    #[test]
+    fn test_merge_policies() {
+        let mint = Pubkey::new_unique();
+        let plans = || vec![sell(mint, 0.5, "take_profit"), sell(mint, 1.0, "follow_sell")];
+
+        let out = merge(plans(), MergePolicy::Priority);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].strategy.as_deref(), Some("take_profit"));
+
+        let out = merge(plans(), MergePolicy::Largest);
+        assert_eq!(out[0].strategy.as_deref(), Some("follow_sell"));
+
+        let out = merge(plans(), MergePolicy::Sum);
+        assert_eq!(out[0].sell_pct, Some(1.0));
+        assert_eq!(out[0].strategy.as_deref(), Some("take_profit+follow_sell"));
+    }
+
+    #[test]
+    fn test_priority_keeps_stop_loss_over_follow_sell() {
+        let mint = Pubkey::new_unique();
+        let plans = vec![sell(mint, 0.1, "follow_sell"), sell(mint, 1.0, "stop_loss")];
+
+        let out = merge(plans, MergePolicy::Priority);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].strategy.as_deref(), Some("stop_loss"));
+        assert_eq!(out[0].sell_pct, Some(1.0));
+    }
+}
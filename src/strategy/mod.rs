@@ -1,24 +1,35 @@
 // ---------- File replaces: src/strategy/mod.rs ----------
 //! Thin strategy layer – now with an engine dispatcher.
 
+use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 
+pub mod consensus_buy;
+pub mod creator_watch;
 pub mod engine;
+pub mod filters;
 pub mod follow_buy;
 pub mod follow_sell;
+pub mod max_hold;
+pub mod merge;
+pub mod registry;
+pub mod risk;
+pub mod rug_watch;
+pub mod stop_loss;
 pub mod take_profit;
+pub mod trailing_stop;
 
 use crate::config::settings::Settings;
 
 // pub mod take_profit;   // keep as soon as the file exists
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Side {
     Buy,
     Sell,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DexKind {
     Pumpfun,
     PumpSwap,  // Migrated PumpFun tokens using PumpSwap AMM
@@ -39,6 +50,11 @@ pub struct TradePlan {
     pub sell_pct: Option<f64>, // SELL only (0.0 – 1.0)
     pub known_token_amount: Option<u64>, // SELL only - skip ATA polling if provided
     pub calculated_token_amount: Option<u64>, // BUY only - actual min_out from calculation
+    pub wallet_label: Option<String>, // tracked wallet whose signal produced this plan
+    pub strategy: Option<String>,     // registry name of the strategy that produced it
+    pub urgent: bool,                 // SELL only - pay the emergency sell tip
+    pub take_profit_rungs: Vec<usize>, // SELL only - ladder rungs marked fired once booked
+    pub follow_sell_pct: Option<f64>,  // SELL only - part of `sell_pct` mirroring `wallet_label`
 }
 
 impl TradePlan {
@@ -52,6 +68,11 @@ impl TradePlan {
             sell_pct: None,
             known_token_amount: None,
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -65,6 +86,11 @@ impl TradePlan {
             sell_pct: Some(pct),
             known_token_amount: None,
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -78,6 +104,11 @@ impl TradePlan {
             sell_pct: Some(pct),
             known_token_amount: Some(token_amount),
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -91,6 +122,11 @@ impl TradePlan {
             sell_pct: None,
             known_token_amount: None,
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -104,6 +140,11 @@ impl TradePlan {
             sell_pct: Some(pct),
             known_token_amount: None,
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -117,6 +158,11 @@ impl TradePlan {
             sell_pct: Some(pct),
             known_token_amount: Some(token_amount),
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -130,6 +176,11 @@ impl TradePlan {
             sell_pct: None,
             known_token_amount: None,
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -143,6 +194,11 @@ impl TradePlan {
             sell_pct: Some(pct),
             known_token_amount: None,
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -156,6 +212,11 @@ impl TradePlan {
             sell_pct: Some(pct),
             known_token_amount: Some(token_amount),
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -169,6 +230,11 @@ impl TradePlan {
             sell_pct: None,
             known_token_amount: None,
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -182,6 +248,11 @@ impl TradePlan {
             sell_pct: Some(pct),
             known_token_amount: None,
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -195,6 +266,11 @@ impl TradePlan {
             sell_pct: Some(pct),
             known_token_amount: Some(token_amount),
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -208,6 +284,11 @@ impl TradePlan {
             sell_pct: None,
             known_token_amount: None,
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -221,6 +302,11 @@ impl TradePlan {
             sell_pct: Some(pct),
             known_token_amount: None,
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -234,6 +320,11 @@ impl TradePlan {
             sell_pct: Some(pct),
             known_token_amount: Some(token_amount),
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -247,6 +338,11 @@ impl TradePlan {
             sell_pct: None,
             known_token_amount: None,
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -260,6 +356,11 @@ impl TradePlan {
             sell_pct: Some(pct),
             known_token_amount: None,
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
         }
     }
 
@@ -273,6 +374,47 @@ impl TradePlan {
             sell_pct: Some(pct),
             known_token_amount: Some(token_amount),
             calculated_token_amount: None,
+            wallet_label: None,
+            strategy: None,
+            urgent: false,
+            take_profit_rungs: Vec::new(),
+            follow_sell_pct: None,
+        }
+    }
+
+    /// Attribute the plan to a tracked wallet so its trading profile applies
+    pub fn with_wallet(mut self, label: &str) -> Self {
+        self.wallet_label = Some(label.to_string());
+        self
+    }
+
+    /// Mark the plan as an emergency exit (see `emergency_sell_bribe_sol`)
+    pub fn urgent(mut self) -> Self {
+        self.urgent = true;
+        self
+    }
+
+    /// Helper for a BUY of `lamports` on whichever DEX the mint trades on
+    pub fn buy(dex: DexKind, mint: Pubkey, lamports: u64) -> Self {
+        match dex {
+            DexKind::Pumpfun => Self::buy_pumpfun(mint, lamports),
+            DexKind::PumpSwap => Self::buy_pumpswap(mint, lamports),
+            DexKind::Moonshot => Self::buy_moonshot(mint, lamports),
+            DexKind::Raydium => Self::buy_raydium(mint, lamports),
+            DexKind::Meteora => Self::buy_meteora(mint, lamports),
+            DexKind::RaydiumLaunchpad => Self::buy_raydium_launchpad(mint, lamports),
+        }
+    }
+
+    /// Helper for a SELL by % on whichever DEX the mint trades on
+    pub fn sell_percent(dex: DexKind, mint: Pubkey, pct: f64) -> Self {
+        match dex {
+            DexKind::Pumpfun => Self::sell_pumpfun_percent(mint, pct),
+            DexKind::PumpSwap => Self::sell_pumpswap_percent(mint, pct),
+            DexKind::Moonshot => Self::sell_moonshot_percent(mint, pct),
+            DexKind::Raydium => Self::sell_raydium_percent(mint, pct),
+            DexKind::Meteora => Self::sell_meteora_percent(mint, pct),
+            DexKind::RaydiumLaunchpad => Self::sell_raydium_launchpad_percent(mint, pct),
         }
     }
 }
@@ -286,8 +428,49 @@ pub struct ObservedFill {
     pub pct_of_balance: f64,
     pub dex: DexKind,
     pub wallet_label: String, // Human-readable wallet label
+    pub slot: u64,            // Slot the tracked transaction landed in (0 if unknown)
+    pub trader_sol_balance: Option<u64>, // Tracked wallet's lamports before the trade
+}
+
+/// The coin creator of a mint moving tokens of it out of their wallet, seen
+/// on the Geyser stream through the creators added by `creator_watch`.
+#[derive(Clone, Debug)]
+pub struct CreatorActivity {
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    pub tokens_out: u64,
+    pub pct_of_balance: f64, // Share of the creator's balance that left (0.0 – 1.0)
+    pub sold: bool,          // Sold into a pool; false for a plain transfer
+    pub slot: u64,
+}
+
+/// An update of an account subscribed through `rpc::geyser_filter`
+/// (e.g. a pool reserve vault watched by `rug_watch`).
+#[derive(Clone, Debug)]
+pub struct AccountUpdate {
+    pub pubkey: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub slot: u64,
 }
 
 pub trait Strategy: Send {
     fn on_fill(&mut self, fill: &ObservedFill, settings: &Settings) -> Vec<TradePlan>;
+
+    /// Periodic hook driven by [`engine::StrategyEngine::on_tick`] so exits can
+    /// fire on time or price changes alone, without a tracked‑wallet fill.
+    fn on_tick(&mut self, _settings: &Settings) -> Vec<TradePlan> {
+        Vec::new()
+    }
+
+    /// Creator sell / transfer hook, see [`CreatorActivity`].
+    fn on_creator_activity(&mut self, _ev: &CreatorActivity, _settings: &Settings) -> Vec<TradePlan> {
+        Vec::new()
+    }
+
+    /// Account update hook, see [`AccountUpdate`].
+    fn on_account(&mut self, _update: &AccountUpdate, _settings: &Settings) -> Vec<TradePlan> {
+        Vec::new()
+    }
 }
@@ -0,0 +1,65 @@
+//! Named strategy factories.
+//!
+//! The engine builds its strategy list from the `strategies` section of the
+//! settings file by looking every entry up here. Built‑ins are registered on
+//! first use; anything else only has to call [`register`] before the engine
+//! is constructed.
+
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::strategy::{
+    consensus_buy::ConsensusBuy, creator_watch::CreatorWatch, follow_buy::FollowBuy, follow_sell::FollowSell, max_hold::MaxHold, rug_watch::RugWatch, stop_loss::StopLoss,
+    take_profit::TakeProfit, trailing_stop::TrailingStop, Strategy,
+};
+
+/// Builds a strategy from the `params` object of its settings entry.
+pub type StrategyFactory = fn(&serde_json::Value) -> Result<Box<dyn Strategy + Send>>;
+
+static REGISTRY: Lazy<RwLock<HashMap<String, StrategyFactory>>> = Lazy::new(|| {
+    let mut map: HashMap<String, StrategyFactory> = HashMap::new();
+    map.insert("follow_buy".into(), |_| Ok(Box::new(FollowBuy)));
+    map.insert("consensus_buy".into(), |p| Ok(Box::new(params::<ConsensusBuy>(p)?)));
+    map.insert("follow_sell".into(), |p| Ok(Box::new(params::<FollowSell>(p)?)));
+    map.insert("take_profit".into(), |p| Ok(Box::new(params::<TakeProfit>(p)?)));
+    map.insert("stop_loss".into(), |p| Ok(Box::new(params::<StopLoss>(p)?)));
+    map.insert("trailing_stop".into(), |p| Ok(Box::new(params::<TrailingStop>(p)?)));
+    map.insert("max_hold".into(), |p| Ok(Box::new(params::<MaxHold>(p)?)));
+    map.insert("creator_watch".into(), |p| Ok(Box::new(params::<CreatorWatch>(p)?)));
+    map.insert("rug_watch".into(), |p| Ok(Box::new(params::<RugWatch>(p)?)));
+    RwLock::new(map)
+});
+
+/// Register (or replace) a strategy factory under `name`.
+pub fn register(name: &str, factory: StrategyFactory) {
+    REGISTRY.write().unwrap().insert(name.to_string(), factory);
+}
+
+/// Build the strategy registered as `name` with the given parameters.
+pub fn build(name: &str, params: &serde_json::Value) -> Result<Box<dyn Strategy + Send>> {
+    let factory = REGISTRY
+        .read()
+        .unwrap()
+        .get(name)
+        .copied()
+        .ok_or_else(|| anyhow!("unknown strategy `{name}` (known: {:?})", names()))?;
+    factory(params).with_context(|| format!("building strategy `{name}`"))
+}
+
+/// All registered strategy names, sorted.
+pub fn names() -> Vec<String> {
+    let mut names: Vec<String> = REGISTRY.read().unwrap().keys().cloned().collect();
+    names.sort();
+    names
+}
+
+/// Deserialize typed strategy parameters; a missing `params` means defaults.
+pub fn params<T: DeserializeOwned + Default>(value: &serde_json::Value) -> Result<T> {
+    if value.is_null() {
+        return Ok(T::default());
+    }
+    serde_json::from_value(value.clone()).context("parsing strategy params")
+}
@@ -0,0 +1,276 @@
+//! Portfolio risk layer between the strategies and execution.
+//!
+//! Every BUY plan the engine produces is checked against [`RiskConfig`]
+//! before it is handed on; sells always pass. Accepted buys count as
+//! in‑flight exposure until their position shows up, so a burst of
+//! tracked‑wallet buys cannot slip past the limits before any of them lands.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::config::settings::{RiskConfig, Settings};
+use crate::positions::PositionManager;
+use crate::strategy::{Side, TradePlan};
+
+/// An accepted buy stops counting as in‑flight after this long, landed or not.
+const PENDING_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RiskRule {
+    DailyLossLimit,
+    WalletCooldown,
+    MaxOpenPositions,
+    MaxTotalExposure,
+    MaxMintExposure,
+}
+
+impl fmt::Display for RiskRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RiskRule::DailyLossLimit => "daily_loss_limit_sol",
+            RiskRule::WalletCooldown => "wallet_cooldown_seconds",
+            RiskRule::MaxOpenPositions => "max_open_positions",
+            RiskRule::MaxTotalExposure => "max_total_exposure_sol",
+            RiskRule::MaxMintExposure => "max_mint_exposure_sol",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Portfolio state a BUY is judged against.
+#[derive(Debug, Default, Clone, Copy)]
+struct Exposure {
+    open_positions: usize,
+    total_lamports: u64,
+    mint_lamports: u64,
+    /// Whether the plan's mint is already held or in flight
+    mint_held: bool,
+    realized_today_lamports: i64,
+}
+
+#[derive(Debug)]
+struct PendingBuy {
+    mint: Pubkey,
+    lamports: u64,
+    at: Instant,
+    /// The mint's (balance, cost) when the buy was accepted
+    book_before: (u128, u64),
+}
+
+/// The book's (balance, cost) of `mint` across owners; a buy landing
+/// changes it, a price update doesn't.
+fn book_state(pm: &PositionManager, mint: Pubkey) -> (u128, u64) {
+    pm.holding(mint)
+        .map_or((0, 0), |h| (h.balance, h.cost_lamports))
+}
+
+#[derive(Debug, Default)]
+pub struct RiskManager {
+    pending: Vec<PendingBuy>,
+    last_wallet_buy: HashMap<String, Instant>,
+}
+
+impl RiskManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep the plans that pass every limit; log the ones that don't.
+    pub fn filter(
+        &mut self,
+        plans: Vec<TradePlan>,
+        settings: &Settings,
+        pm: &PositionManager,
+    ) -> Vec<TradePlan> {
+        let cfg = &settings.risk;
+        let now = Instant::now();
+        self.prune(now, cfg, pm);
+
+        let mut out = Vec::with_capacity(plans.len());
+        for plan in plans {
+            if plan.side == Side::Sell {
+                out.push(plan);
+                continue;
+            }
+
+            let label = plan.wallet_label.as_deref().unwrap_or("-");
+            if let (Some(cooldown), Some(last)) =
+                (cfg.wallet_cooldown_seconds, self.last_wallet_buy.get(label))
+            {
+                let elapsed = now.duration_since(*last);
+                if elapsed < Duration::from_secs(cooldown) {
+                    let detail = format!(
+                        "last buy from {} was {:.1}s ago",
+                        label,
+                        elapsed.as_secs_f64()
+                    );
+                    reject(&plan, RiskRule::WalletCooldown, &detail);
+                    continue;
+                }
+            }
+
+            let exposure = self.exposure(&plan.mint, pm);
+            if let Err((rule, detail)) = check(cfg, &exposure, plan.buy_lamports) {
+                reject(&plan, rule, &detail);
+                continue;
+            }
+
+            self.pending.push(PendingBuy {
+                mint: plan.mint,
+                lamports: plan.buy_lamports,
+                at: now,
+                book_before: book_state(pm, plan.mint),
+            });
+            if let Some(label) = &plan.wallet_label {
+                self.last_wallet_buy.insert(label.clone(), now);
+            }
+            out.push(plan);
+        }
+        out
+    }
+
+    /// Forget in‑flight buys that expired or whose mint's balance or cost
+    /// has changed since they were accepted.
+    fn prune(&mut self, now: Instant, cfg: &RiskConfig, pm: &PositionManager) {
+        self.pending.retain(|p| {
+            let landed = book_state(pm, p.mint) != p.book_before;
+            !landed && now.duration_since(p.at) < PENDING_TTL
+        });
+        let cooldown = Duration::from_secs(cfg.wallet_cooldown_seconds.unwrap_or(0));
+        self.last_wallet_buy
+            .retain(|_, at| now.duration_since(*at) < cooldown);
+    }
+
+    fn exposure(&self, mint: &Pubkey, pm: &PositionManager) -> Exposure {
+        // in‑flight mints we don't hold yet each open a new position
+        let mut distinct: Vec<&Pubkey> = self
+            .pending
+            .iter()
+            .map(|p| &p.mint)
+            .filter(|m| pm.holding(**m).map_or(true, |h| h.balance == 0))
+            .collect();
+        distinct.sort();
+        distinct.dedup();
+
+        let pending_total: u64 = self.pending.iter().map(|p| p.lamports).sum();
+        let pending_mint: u64 = self
+            .pending
+            .iter()
+            .filter(|p| p.mint == *mint)
+            .map(|p| p.lamports)
+            .sum();
+        // limits are portfolio-wide: a mint counts once across owners
+        let held = pm.holding(*mint).filter(|h| h.balance > 0);
+
+        Exposure {
+            open_positions: pm.open_count() + distinct.len(),
+            total_lamports: pm.exposure_lamports() + pending_total,
+            mint_lamports: held.as_ref().map_or(0, |h| h.cost_lamports) + pending_mint,
+            mint_held: held.is_some() || pending_mint > 0,
+            realized_today_lamports: pm.realized_today_lamports(),
+        }
+    }
+}
+
+fn sol(lamports: u64) -> f64 {
+    lamports as f64 / 1_000_000_000.0
+}
+
+fn lamports(sol: f64) -> u64 {
+    (sol * 1_000_000_000.0).round() as u64
+}
+
+/// The first limit a BUY of `buy_lamports` would break, if any.
+fn check(cfg: &RiskConfig, e: &Exposure, buy_lamports: u64) -> Result<(), (RiskRule, String)> {
+    if let Some(limit) = cfg.daily_loss_limit_sol {
+        if e.realized_today_lamports <= -(lamports(limit) as i64) {
+            return Err((
+                RiskRule::DailyLossLimit,
+                format!(
+                    "realized today {:.4} SOL, limit -{:.4} SOL – buying halted",
+                    e.realized_today_lamports as f64 / 1_000_000_000.0,
+                    limit
+                ),
+            ));
+        }
+    }
+    if let Some(max) = cfg.max_open_positions {
+        if !e.mint_held && e.open_positions >= max {
+            return Err((
+                RiskRule::MaxOpenPositions,
+                format!("{} open, max {}", e.open_positions, max),
+            ));
+        }
+    }
+    if let Some(max) = cfg.max_total_exposure_sol {
+        if e.total_lamports + buy_lamports > lamports(max) {
+            return Err((
+                RiskRule::MaxTotalExposure,
+                format!(
+                    "{:.4} + {:.4} SOL > {:.4} SOL",
+                    sol(e.total_lamports),
+                    sol(buy_lamports),
+                    max
+                ),
+            ));
+        }
+    }
+    if let Some(max) = cfg.max_mint_exposure_sol {
+        if e.mint_lamports + buy_lamports > lamports(max) {
+            return Err((
+                RiskRule::MaxMintExposure,
+                format!(
+                    "{:.4} + {:.4} SOL > {:.4} SOL",
+                    sol(e.mint_lamports),
+                    sol(buy_lamports),
+                    max
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn reject(plan: &TradePlan, rule: RiskRule, detail: &str) {
+    println!(
+        "🛑 [RISK] Rejected BUY {} ({:.4} SOL, wallet {}) – {}: {}",
+        plan.mint,
+        sol(plan.buy_lamports),
+        plan.wallet_label.as_deref().unwrap_or("-"),
+        rule,
+        detail
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_rules() {
+        let cfg = RiskConfig {
+            max_open_positions: Some(2),
+            max_total_exposure_sol: Some(1.0),
+            max_mint_exposure_sol: Some(0.3),
+            daily_loss_limit_sol: Some(0.5),
+            wallet_cooldown_seconds: None,
+        };
+        let base = Exposure { open_positions: 1, total_lamports: lamports(0.5), ..Default::default() };
+        assert!(check(&cfg, &base, lamports(0.2)).is_ok());
+
+        let full = Exposure { open_positions: 2, ..base };
+        assert_eq!(check(&cfg, &full, lamports(0.1)).unwrap_err().0, RiskRule::MaxOpenPositions);
+        // adding to a held mint does not open a new position
+        let held = Exposure { mint_held: true, mint_lamports: lamports(0.1), ..full };
+        assert!(check(&cfg, &held, lamports(0.1)).is_ok());
+        assert_eq!(check(&cfg, &held, lamports(0.25)).unwrap_err().0, RiskRule::MaxMintExposure);
+
+        assert_eq!(check(&cfg, &base, lamports(0.6)).unwrap_err().0, RiskRule::MaxTotalExposure);
+
+        let losing = Exposure { realized_today_lamports: -(lamports(0.5) as i64), ..base };
+        assert_eq!(check(&cfg, &losing, 1).unwrap_err().0, RiskRule::DailyLossLimit);
+    }
+}
@@ -0,0 +1,327 @@
+use super::*;
+use crate::config::settings::Settings;
+use crate::dex::{pump_amm, raydium, router::program_ids};
+use crate::rpc::geyser_filter;
+use crate::strategy::engine::STRATEGY_ENGINE;
+use crate::utils::pool_tracker::{self, PoolReserves};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+
+/// Name under which the reserve accounts are added to the Geyser filter.
+pub const WATCHER: &str = "rug_watch";
+
+/// Watch the reserves behind every open position and exit in full, with the
+/// emergency sell tip, when the quote side (SOL) drops by more than
+/// `max_quote_drop_pct` – or the LP supply by more than `max_lp_drop_pct` –
+/// within `window_slots`. The position is tagged as rugged.
+///
+/// Watched accounts: the Pump.fun bonding curve, the PumpSwap pool's WSOL
+/// vault, and the Raydium CPMM WSOL vault and LP mint. Other venues are not
+/// covered.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RugWatch {
+    /// Slots a drop is measured over
+    pub window_slots: u64,
+    /// Drop of SOL reserves from the window's peak that counts as a pull (%)
+    pub max_quote_drop_pct: f64,
+    /// Drop of LP supply from the window's peak that counts as LP removal (%)
+    pub max_lp_drop_pct: f64,
+
+    /// mint → (venue the accounts were derived for, its reserve accounts)
+    #[serde(skip)]
+    pools: HashMap<Pubkey, (DexKind, Vec<(Pubkey, Reserve)>)>,
+    /// reserve account → (mint, kind)
+    #[serde(skip)]
+    accounts: HashMap<Pubkey, (Pubkey, Reserve)>,
+    /// reserve account → recent values
+    #[serde(skip)]
+    history: HashMap<Pubkey, VecDeque<Sample>>,
+    /// Mints already exited, until the position is gone
+    #[serde(skip)]
+    flagged: HashSet<Pubkey>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Reserve {
+    /// Pump.fun bonding curve account (real SOL reserves)
+    BondingCurve,
+    /// SPL token account holding the pool's WSOL
+    QuoteVault,
+    /// LP mint (supply)
+    LpMint,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Sample {
+    slot: u64,
+    value: u64,
+}
+
+impl Default for RugWatch {
+    fn default() -> Self {
+        Self {
+            window_slots: 20,
+            max_quote_drop_pct: 50.0,
+            max_lp_drop_pct: 50.0,
+            pools: HashMap::new(),
+            accounts: HashMap::new(),
+            history: HashMap::new(),
+            flagged: HashSet::new(),
+        }
+    }
+}
+
+/// Reserve accounts of `mint`'s pool on `dex`, if we know how to watch it.
+fn reserve_accounts(mint: &Pubkey, dex: DexKind) -> Vec<(Pubkey, Reserve)> {
+    match dex {
+        DexKind::Pumpfun => {
+            let program = Pubkey::from_str(program_ids::PUMPFUN_PROGRAM_ID).unwrap();
+            let (curve, _) =
+                Pubkey::find_program_address(&[b"bonding-curve", mint.as_ref()], &program);
+            vec![(curve, Reserve::BondingCurve)]
+        }
+        DexKind::PumpSwap => {
+            let (pool, _) = pump_amm::derive_canonical_pump_pool(mint);
+            let vault = spl_associated_token_account::get_associated_token_address(
+                &pool,
+                &pump_amm::WSOL_MINT,
+            );
+            vec![(vault, Reserve::QuoteVault)]
+        }
+        DexKind::Raydium => {
+            let keys = match raydium::RaydiumDex::new()
+                .and_then(|r| r.derive_pool_keys_for_migrated_token(mint))
+            {
+                Ok(keys) => keys,
+                Err(_) => return Vec::new(),
+            };
+            let vault = if keys.base_mint == raydium::WSOL_MINT {
+                keys.base_vault
+            } else {
+                keys.quote_vault
+            };
+            vec![
+                (vault, Reserve::QuoteVault),
+                (keys.lp_mint, Reserve::LpMint),
+            ]
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn read_u64(data: &[u8], at: usize) -> Option<u64> {
+    data.get(at..at + 8)
+        .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+}
+
+/// The watched value of a reserve account, or `None` if it can't be read or
+/// no longer applies (a completed bonding curve migrated, it wasn't pulled).
+fn decode(kind: Reserve, data: &[u8]) -> Option<u64> {
+    match kind {
+        // disc, virtual token, virtual sol, real token, real sol, supply, complete
+        Reserve::BondingCurve => match data.get(48) {
+            Some(0) => read_u64(data, 32),
+            _ => None,
+        },
+        // SPL token account: mint, owner, amount
+        Reserve::QuoteVault => read_u64(data, 64),
+        // SPL mint: mint authority option, supply
+        Reserve::LpMint => read_u64(data, 36),
+    }
+}
+
+/// Add `sample` to `history`, drop samples older than `window_slots`, and
+/// return the drop from the window's peak to `sample` in percent.
+fn record(history: &mut VecDeque<Sample>, sample: Sample, window_slots: u64) -> f64 {
+    while history
+        .front()
+        .map_or(false, |s| s.slot.saturating_add(window_slots) < sample.slot)
+    {
+        history.pop_front();
+    }
+    history.push_back(sample);
+
+    let peak = history.iter().map(|s| s.value).max().unwrap_or(0);
+    if peak == 0 {
+        return 0.0;
+    }
+    (peak - sample.value) as f64 / peak as f64 * 100.0
+}
+
+impl RugWatch {
+    /// Re-derive the reserve accounts of `owner`'s open positions and push
+    /// them to the Geyser account subscription.
+    fn refresh(&mut self, owner: Pubkey) {
+        let engine = match STRATEGY_ENGINE.get() {
+            Some(engine) => engine,
+            None => return,
+        };
+        let held: HashMap<Pubkey, DexKind> = {
+            let pm = engine.positions.lock().unwrap();
+            pm.owned_by(owner)
+                .filter(|p| p.balance > 0)
+                .filter_map(|p| p.dex.map(|dex| (p.mint, dex)))
+                .collect()
+        };
+
+        self.pools
+            .retain(|mint, (dex, _)| held.get(mint) == Some(dex));
+        self.flagged.retain(|mint| held.contains_key(mint));
+        for (mint, dex) in &held {
+            if !self.pools.contains_key(mint) {
+                self.pools
+                    .insert(*mint, (*dex, reserve_accounts(mint, *dex)));
+            }
+        }
+
+        self.accounts = self
+            .pools
+            .iter()
+            .flat_map(|(mint, (_, accounts))| {
+                accounts.iter().map(move |(a, kind)| (*a, (*mint, *kind)))
+            })
+            .collect();
+        let accounts = &self.accounts;
+        self.history
+            .retain(|account, _| accounts.contains_key(account));
+        geyser_filter::set_account_watch(WATCHER, self.accounts.keys().copied().collect());
+    }
+}
+
+impl Strategy for RugWatch {
+    fn on_fill(&mut self, _f: &ObservedFill, _settings: &Settings) -> Vec<TradePlan> {
+        Vec::new()
+    }
+
+    fn on_tick(&mut self, settings: &Settings) -> Vec<TradePlan> {
+        self.refresh(settings.owner());
+        Vec::new()
+    }
+
+    fn on_account(&mut self, update: &AccountUpdate, settings: &Settings) -> Vec<TradePlan> {
+        let (mint, kind) = match self.accounts.get(&update.pubkey) {
+            Some(watched) => *watched,
+            None => return Vec::new(),
+        };
+        if self.flagged.contains(&mint) {
+            return Vec::new();
+        }
+        let value = match decode(kind, &update.data) {
+            Some(value) => value,
+            None => {
+                self.history.remove(&update.pubkey);
+                return Vec::new();
+            }
+        };
+
+        if kind == Reserve::BondingCurve {
+            // the curve carries the full price state; share it (paper fills)
+            if let (Some(token_reserve), Some(sol_reserve)) =
+                (read_u64(&update.data, 8), read_u64(&update.data, 16))
+            {
+                pool_tracker::record_reserves(
+                    mint,
+                    PoolReserves {
+                        dex: DexKind::Pumpfun,
+                        token_reserve,
+                        sol_reserve,
+                        slot: update.slot,
+                    },
+                );
+            }
+        }
+
+        let history = self.history.entry(update.pubkey).or_default();
+        let drop_pct = record(
+            history,
+            Sample {
+                slot: update.slot,
+                value,
+            },
+            self.window_slots,
+        );
+        let limit = match kind {
+            Reserve::LpMint => self.max_lp_drop_pct,
+            _ => self.max_quote_drop_pct,
+        };
+        if drop_pct < limit {
+            return Vec::new();
+        }
+
+        let dex = match STRATEGY_ENGINE.get().and_then(|engine| {
+            let mut pm = engine.positions.lock().unwrap();
+            if let Err(e) = pm.mark_rugged(mint) {
+                println!(
+                    "⚠️ [RUG_WATCH] Failed to persist rugged flag for {}: {}",
+                    mint, e
+                );
+            }
+            pm.position(settings.owner(), mint)
+                .filter(|p| p.balance > 0)
+                .and_then(|p| p.dex)
+        }) {
+            Some(dex) => dex,
+            None => return Vec::new(),
+        };
+
+        self.flagged.insert(mint);
+        println!(
+            "🚨 [RUG_WATCH] {} {} dropped {:.1}% within {} slots (slot {}) → emergency sell 100%",
+            mint,
+            if kind == Reserve::LpMint {
+                "LP supply"
+            } else {
+                "SOL reserves"
+            },
+            drop_pct,
+            self.window_slots,
+            update.slot
+        );
+        vec![TradePlan::sell_percent(dex, mint, 1.0).urgent()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_measures_drop_within_window() {
+        let mut h = VecDeque::new();
+        assert_eq!(
+            record(
+                &mut h,
+                Sample {
+                    slot: 100,
+                    value: 1_000
+                },
+                10
+            ),
+            0.0
+        );
+        assert_eq!(
+            record(
+                &mut h,
+                Sample {
+                    slot: 105,
+                    value: 400
+                },
+                10
+            ),
+            60.0
+        );
+        // the peak at slot 100 is out of the window by slot 111
+        assert_eq!(
+            record(
+                &mut h,
+                Sample {
+                    slot: 111,
+                    value: 300
+                },
+                10
+            ),
+            25.0
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use super::*;
+use crate::config::settings::{Settings, TradingProfile};
+use crate::positions::Position;
+use crate::strategy::engine::STRATEGY_ENGINE;
+
+/// Params from the `strategies` settings section; unset values fall back to
+/// the global `stop_loss_*` settings. The profile of the tracked wallet that
+/// opened the position wins over both.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StopLoss {
+    pub percent: Option<f64>,
+    pub sell_fraction: Option<f64>,
+}
+
+impl StopLoss {
+    /// Threshold (wallet profile → strategy param → global), normalised to a
+    /// negative PnL %. Accepts both `-50` and `50`.
+    fn threshold(&self, profile: Option<&TradingProfile>, settings: &Settings) -> Option<f64> {
+        profile
+            .and_then(|p| p.stop_loss_percent)
+            .or(self.percent)
+            .or(settings.stop_loss_percent)
+            .map(|t| -t.abs())
+    }
+
+    fn sell_fraction(&self, profile: Option<&TradingProfile>, settings: &Settings) -> f64 {
+        profile
+            .and_then(|p| p.stop_loss_sell_fraction)
+            .or(self.sell_fraction)
+            .unwrap_or(settings.stop_loss_sell_fraction)
+    }
+
+    /// Sell plan if `pos` is at or below the stop-loss threshold.
+    fn check(&self, pos: &Position, dex: DexKind, settings: &Settings) -> Option<TradePlan> {
+        let profile = pos.opened_by.as_deref().and_then(|l| settings.profile(l));
+        // No threshold anywhere = disabled
+        let threshold = self.threshold(profile, settings)?;
+        if pos.balance == 0 {
+            return None;
+        }
+
+        let pnl = pos.unrealised_pnl_pct()?;
+        if pnl > threshold {
+            return None;
+        }
+
+        println!("🛑 [STOP_LOSS] Triggering stop-loss for {}: {:.2}% <= {:.2}% threshold",
+            pos.mint, pnl, threshold);
+
+        Some(TradePlan::sell_percent(
+            dex,
+            pos.mint,
+            self.sell_fraction(profile, settings),
+        ))
+    }
+}
+
+impl Strategy for StopLoss {
+    fn on_fill(&mut self, f: &ObservedFill, settings: &Settings) -> Vec<TradePlan> {
+        if let Some(engine) = STRATEGY_ENGINE.get() {
+            let pm = engine.positions.lock().unwrap();
+
+            // Nothing to protect if we do not hold the mint
+            if let Some(pos) = pm.position(settings.owner(), f.mint) {
+                return self.check(pos, f.dex, settings).into_iter().collect();
+            }
+        }
+        Vec::new()
+    }
+
+    fn on_tick(&mut self, settings: &Settings) -> Vec<TradePlan> {
+        let mut out = Vec::new();
+        if let Some(engine) = STRATEGY_ENGINE.get() {
+            let pm = engine.positions.lock().unwrap();
+            for pos in pm.owned_by(settings.owner()) {
+                if let Some(dex) = pos.dex {
+                    out.extend(self.check(pos, dex, settings));
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::settings::test_settings;
+    use crate::positions::new_position;
+
+    #[test]
+    fn test_tick_exit_uses_openers_threshold() {
+        let settings = test_settings(serde_json::json!({
+            "stop_loss_percent": -10,
+            "tracked_wallets": [{
+                "label": "patient",
+                "address": Pubkey::new_unique().to_string(),
+                "stop_loss_percent": -50
+            }]
+        }));
+        let mut pos = new_position(settings.owner(), Pubkey::new_unique(), Some("patient"), 0);
+        pos.balance = 1_000;
+        pos.cost_lamports = 1_000;
+        pos.last_price = Some(0.8); // -20 %
+
+        let stop = StopLoss::default();
+        assert!(stop.check(&pos, DexKind::Pumpfun, &settings).is_none());
+        pos.last_price = Some(0.4); // -60 %
+        assert!(stop.check(&pos, DexKind::Pumpfun, &settings).is_some());
+
+        // a position nobody opened falls back to the global -10 %
+        pos.opened_by = None;
+        pos.last_price = Some(0.8);
+        assert!(stop.check(&pos, DexKind::Pumpfun, &settings).is_some());
+    }
+}
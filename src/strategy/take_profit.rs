@@ -1,8 +1,86 @@
 use super::*;
-use crate::config::settings::Settings;
+use crate::config::settings::{Settings, TakeProfitRung};
+use crate::positions::PositionManager;
 use crate::strategy::engine::STRATEGY_ENGINE;
 
-pub struct TakeProfit;
+/// Params from the `strategies` settings section; an unset `ladder` falls
+/// back to the global `take_profit_ladder` setting.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TakeProfit {
+    pub ladder: Option<Vec<TakeProfitRung>>,
+}
+
+/// Rungs that should fire now and the share of the *current* balance to sell.
+///
+/// Rung fractions are relative to the position at entry, so they are rescaled
+/// by whatever the already‑fired rungs left over.
+fn rungs_to_fire(ladder: &[TakeProfitRung], fired: &[usize], pnl: f64) -> Option<(Vec<usize>, f64)> {
+    let hits: Vec<usize> = ladder
+        .iter()
+        .enumerate()
+        .filter(|(i, rung)| !fired.contains(i) && pnl >= rung.at_percent)
+        .map(|(i, _)| i)
+        .collect();
+    if hits.is_empty() {
+        return None;
+    }
+
+    let sold: f64 = fired
+        .iter()
+        .filter_map(|&i| ladder.get(i))
+        .map(|rung| rung.sell_fraction)
+        .sum();
+    let selling: f64 = hits.iter().map(|&i| ladder[i].sell_fraction).sum();
+    let remaining = 1.0 - sold;
+
+    let pct = if remaining <= f64::EPSILON {
+        1.0
+    } else {
+        (selling / remaining).clamp(0.0, 1.0)
+    };
+    Some((hits, pct))
+}
+
+impl TakeProfit {
+    /// Ladder for positions opened by `label` (wallet profile → strategy
+    /// param → global).
+    fn ladder<'a>(&'a self, label: Option<&str>, settings: &'a Settings) -> &'a [TakeProfitRung] {
+        label
+            .and_then(|l| settings.profile(l))
+            .and_then(|p| p.take_profit_ladder.as_deref())
+            .or(self.ladder.as_deref())
+            .unwrap_or(&settings.take_profit_ladder)
+    }
+
+    /// Walk the ladder for one position. The plan carries the rungs it fires;
+    /// they are marked when its sell is booked (`PositionManager::record_sell`),
+    /// so a plan that is dropped or never lands fires them again.
+    fn check(
+        &self,
+        pm: &PositionManager,
+        owner: Pubkey,
+        mint: Pubkey,
+        dex: DexKind,
+        settings: &Settings,
+    ) -> Option<TradePlan> {
+        let pos = pm.position(owner, mint)?;
+        if pos.balance == 0 {
+            return None;
+        }
+        let ladder = self.ladder(pos.opened_by.as_deref(), settings);
+
+        let pnl = pos.unrealised_pnl_pct()?;
+        let (rungs, pct) = rungs_to_fire(ladder, &pos.take_profit_rungs_fired, pnl)?;
+
+        println!("💰 [TAKE_PROFIT] {} at {:.2}% PnL hit rung(s) {:?} → selling {:.2}% of balance",
+            mint, pnl, rungs, pct * 100.0);
+
+        let mut plan = TradePlan::sell_percent(dex, mint, pct);
+        plan.take_profit_rungs = rungs;
+        Some(plan)
+    }
+}
 
 impl Strategy for TakeProfit {
     fn on_fill(&mut self, f: &ObservedFill, settings: &Settings) -> Vec<TradePlan> {
@@ -16,57 +94,121 @@ impl Strategy for TakeProfit {
             return Vec::new();
         }
 
-        // Update price based on market activity and check for take-profit
         if let Some(engine) = STRATEGY_ENGINE.get() {
             let pm = engine.positions.lock().unwrap();
-            
-            // Check if we have a position in this mint
-            let current_balance = pm.balance(f.mint);
-            if current_balance == 0 {
-                return Vec::new(); // No position, no take-profit
-            }
+            return self
+                .check(&pm, settings.owner(), f.mint, f.dex, settings)
+                .into_iter()
+                .collect();
+        }
+        Vec::new()
+    }
 
-            // Check unrealized PnL
-            if let Some(pnl) = pm.unrealised_pct(f.mint) {
-                println!("🎯 [TAKE_PROFIT] Checking PnL for {}: {:.2}% (threshold: {:.2}%)", 
-                    f.mint, pnl, settings.take_profit_percent);
-                
-                if pnl >= settings.take_profit_percent {
-                    println!("💰 [TAKE_PROFIT] Triggering take-profit: {:.2}% profit >= {:.2}% threshold", 
-                        pnl, settings.take_profit_percent);
-                    
-                    // Create appropriate sell plan based on the DEX where we saw activity
-                    let sell_plan = match f.dex {
-                        DexKind::Pumpfun => TradePlan::sell_pumpfun_percent(
-                            f.mint,
-                            settings.take_profit_sell_fraction,
-                        ),
-                        DexKind::PumpSwap => TradePlan::sell_pumpswap_percent(
-                            f.mint,
-                            settings.take_profit_sell_fraction,
-                        ),
-                        DexKind::Moonshot => TradePlan::sell_moonshot_percent(
-                            f.mint,
-                            settings.take_profit_sell_fraction,
-                        ),
-                        DexKind::Raydium => TradePlan::sell_raydium_percent(
-                            f.mint,
-                            settings.take_profit_sell_fraction,
-                        ),
-                        DexKind::Meteora => TradePlan::sell_meteora_percent(
-                            f.mint,
-                            settings.take_profit_sell_fraction,
-                        ),
-                        DexKind::RaydiumLaunchpad => TradePlan::sell_raydium_launchpad_percent(
-                            f.mint,
-                            settings.take_profit_percent,
-                        ),
-                    };
-                    
-                    return vec![sell_plan];
-                }
+    /// Same ladder check for every open position, so profits are still taken
+    /// when the tracked wallet goes silent on a mint.
+    fn on_tick(&mut self, settings: &Settings) -> Vec<TradePlan> {
+        let mut out = Vec::new();
+        if let Some(engine) = STRATEGY_ENGINE.get() {
+            let pm = engine.positions.lock().unwrap();
+            let owner = settings.owner();
+            let held: Vec<(Pubkey, DexKind)> = pm
+                .owned_by(owner)
+                .filter_map(|pos| pos.dex.map(|dex| (pos.mint, dex)))
+                .collect();
+
+            for (mint, dex) in held {
+                out.extend(self.check(&pm, owner, mint, dex, settings));
             }
         }
-        Vec::new()
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ladder() -> Vec<TakeProfitRung> {
+        vec![
+            TakeProfitRung { at_percent: 50.0, sell_fraction: 0.3 },
+            TakeProfitRung { at_percent: 120.0, sell_fraction: 0.3 },
+            TakeProfitRung { at_percent: 300.0, sell_fraction: 0.4 },
+        ]
+    }
+
+    #[test]
+    fn test_rungs_fire_once_and_rescale() {
+        let ladder = ladder();
+        assert_eq!(rungs_to_fire(&ladder, &[], 20.0), None);
+
+        let (rungs, pct) = rungs_to_fire(&ladder, &[], 60.0).unwrap();
+        assert_eq!(rungs, vec![0]);
+        assert!((pct - 0.3).abs() < 1e-9);
+
+        // Rung 0 already fired → nothing new at the same PnL
+        assert_eq!(rungs_to_fire(&ladder, &[0], 60.0), None);
+
+        // 30% of entry out of the 70% left
+        let (rungs, pct) = rungs_to_fire(&ladder, &[0], 130.0).unwrap();
+        assert_eq!(rungs, vec![1]);
+        assert!((pct - 0.3 / 0.7).abs() < 1e-9);
+
+        // Last rung sells the rest
+        let (_, pct) = rungs_to_fire(&ladder, &[0, 1], 310.0).unwrap();
+        assert!((pct - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rung_fires_again_until_its_sell_is_booked() {
+        use crate::config::settings::{test_settings, MergePolicy};
+        use crate::positions::ledger::FillMeta;
+        use crate::strategy::merge;
+
+        let settings = test_settings(serde_json::json!({}));
+        let tp = TakeProfit {
+            ladder: Some(ladder()),
+        };
+        let (owner, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut pm = PositionManager::in_memory();
+        pm.record_buy(
+            owner,
+            mint,
+            DexKind::Pumpfun,
+            1_000,
+            1_000,
+            &FillMeta::default(),
+        )
+        .unwrap();
+        pm.update_price(mint, 1.6).unwrap(); // +60 %
+
+        let mut plan = tp
+            .check(&pm, owner, mint, DexKind::Pumpfun, &settings)
+            .unwrap();
+        plan.strategy = Some("take_profit".to_string());
+        assert_eq!(plan.take_profit_rungs, vec![0]);
+
+        // follow_sell wins the merge; booking its sell leaves the rung untouched
+        let mut mirrored = TradePlan::sell_percent(DexKind::Pumpfun, mint, 0.1);
+        mirrored.strategy = Some("follow_sell".to_string());
+        let out = merge::merge(vec![mirrored, plan], MergePolicy::Priority);
+        pm.record_sell(owner, mint, 100, 160, &FillMeta::from_plan(&out[0]))
+            .unwrap();
+
+        // next tick: the rung fires again, and is taken once its sell lands
+        let mut plan = tp
+            .check(&pm, owner, mint, DexKind::Pumpfun, &settings)
+            .unwrap();
+        plan.strategy = Some("take_profit".to_string());
+        assert_eq!(plan.take_profit_rungs, vec![0]);
+        pm.record_sell(owner, mint, 270, 432, &FillMeta::from_plan(&plan))
+            .unwrap();
+        assert!(tp.check(&pm, owner, mint, DexKind::Pumpfun, &settings).is_none());
+    }
+
+    #[test]
+    fn test_gap_through_several_rungs() {
+        let (rungs, pct) = rungs_to_fire(&ladder(), &[], 500.0).unwrap();
+        assert_eq!(rungs, vec![0, 1, 2]);
+        assert!((pct - 1.0).abs() < 1e-9);
     }
 }
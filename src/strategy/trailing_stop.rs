@@ -0,0 +1,80 @@
+use super::*;
+use crate::config::settings::Settings;
+use crate::positions::Position;
+use crate::strategy::engine::STRATEGY_ENGINE;
+
+/// Params from the `strategies` settings section; unset values fall back to
+/// the global `trailing_stop_*` settings. The profile of the tracked wallet that
+/// opened the position wins over both.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrailingStop {
+    pub percent: Option<f64>,
+    pub arm_percent: Option<f64>,
+    pub sell_fraction: Option<f64>,
+}
+
+impl TrailingStop {
+    /// Sell plan if `pos` is armed and has pulled back far enough from its peak.
+    fn check(&self, pos: &Position, dex: DexKind, settings: &Settings) -> Option<TradePlan> {
+        let profile = pos.opened_by.as_deref().and_then(|l| settings.profile(l));
+        let trail = profile
+            .and_then(|p| p.trailing_stop_percent)
+            .or(self.percent)
+            .or(settings.trailing_stop_percent)?
+            .abs();
+        let arm = profile
+            .and_then(|p| p.trailing_stop_arm_percent)
+            .or(self.arm_percent)
+            .unwrap_or(settings.trailing_stop_arm_percent);
+        let sell_fraction = profile
+            .and_then(|p| p.trailing_stop_sell_fraction)
+            .or(self.sell_fraction)
+            .unwrap_or(settings.trailing_stop_sell_fraction);
+
+        if pos.balance == 0 {
+            return None;
+        }
+
+        // Only armed once the peak has reached the configured profit
+        let peak_pnl = pos.peak_pnl_pct()?;
+        if peak_pnl < arm {
+            return None;
+        }
+
+        let drawdown = pos.drawdown_from_peak_pct()?;
+        if drawdown > -trail {
+            return None;
+        }
+
+        println!("📉 [TRAILING_STOP] Triggering for {}: {:.2}% below peak (peak PnL {:.2}%, trail {:.2}%)",
+            pos.mint, drawdown, peak_pnl, trail);
+
+        Some(TradePlan::sell_percent(dex, pos.mint, sell_fraction))
+    }
+}
+
+impl Strategy for TrailingStop {
+    fn on_fill(&mut self, f: &ObservedFill, settings: &Settings) -> Vec<TradePlan> {
+        if let Some(engine) = STRATEGY_ENGINE.get() {
+            let pm = engine.positions.lock().unwrap();
+            if let Some(pos) = pm.position(settings.owner(), f.mint) {
+                return self.check(pos, f.dex, settings).into_iter().collect();
+            }
+        }
+        Vec::new()
+    }
+
+    fn on_tick(&mut self, settings: &Settings) -> Vec<TradePlan> {
+        let mut out = Vec::new();
+        if let Some(engine) = STRATEGY_ENGINE.get() {
+            let pm = engine.positions.lock().unwrap();
+            for pos in pm.owned_by(settings.owner()) {
+                if let Some(dex) = pos.dex {
+                    out.extend(self.check(pos, dex, settings));
+                }
+            }
+        }
+        out
+    }
+}
@@ -1,5 +1,18 @@
 //! Geyser-Based Token Balance Tracking
 //! 
 //! Replaces the old token_tracker system with real-time Geyser confirmations.
-//! Tracks exact token amounts from confirmed on-chain transactions.
+//! Tracks exact token amounts from confirmed on-chain transactions, in
+//! `positions::holdings`.
 
+use solana_sdk::pubkey::Pubkey;
+
+use crate::positions::holdings;
+
+/// Geyser confirmed our transaction and our post-balance of `mint` is `amount`
+pub fn on_confirmed_balance(wallet: &Pubkey, mint: &Pubkey, amount: u64) {
+    if amount == 0 {
+        holdings::clear(wallet, mint);
+    } else {
+        holdings::confirm(wallet, mint, Some(amount));
+    }
+}
@@ -1,14 +1,28 @@
-use lazy_static::lazy_static;
 use solana_sdk::pubkey::Pubkey;
 use std::collections::{HashMap, HashSet};
-use tokio::sync::RwLock;
+
+use crate::positions::holdings;
 
 /// Per-wallet we only care whether we *currently* hold a given mint,
-/// so a HashSet<mint> is enough (no quantities yet).
+/// so a HashSet<mint> is enough (quantities are in `positions::holdings`).
 pub type MintBalances = HashSet<Pubkey>;
 
-lazy_static! {
-    /// wallet → set of mints we’re holding
-    pub static ref WALLET_STATE: RwLock<HashMap<Pubkey, MintBalances>> =
-        RwLock::new(HashMap::new());
+/// wallet → set of mints we’re holding (confirmed), read from the holdings store
+pub fn wallet_state() -> HashMap<Pubkey, MintBalances> {
+    let mut out: HashMap<Pubkey, MintBalances> = HashMap::new();
+    for (wallet, mint, holding) in holdings::all() {
+        if holding.is_held() {
+            out.entry(wallet).or_default().insert(mint);
+        }
+    }
+    out
+}
+
+/// Mints `wallet` holds (confirmed)
+pub fn held_mints(wallet: &Pubkey) -> MintBalances {
+    holdings::for_wallet(wallet)
+        .into_iter()
+        .filter(|(_, h)| h.is_held())
+        .map(|(mint, _)| mint)
+        .collect()
 }
@@ -1,4 +1,14 @@
 //! Owned Tokens Cache - Tracks which tokens we actually own
 //! 
-//! This module prevents following sells for tokens we don't own by maintaining
-//! a cache of tokens we've successfully bought and confirmed via Geyser.
\ No newline at end of file
+//! This module prevents following sells for tokens we don't own. The tokens
+//! we've successfully bought and confirmed are kept in `positions::holdings`;
+//! this is the yes/no view of it.
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::positions::holdings;
+
+/// Whether `wallet` holds a confirmed buy of `mint`
+pub fn is_owned(wallet: &Pubkey, mint: &Pubkey) -> bool {
+    holdings::is_held(wallet, mint)
+}
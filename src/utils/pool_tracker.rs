@@ -3,3 +3,131 @@
 //! Keeps a live in‑process registry of on‑chain liquidity pools,
 //! and maps each Pump.fun mint to the BUY / SELL creator‑vault PDAs
 //! we've observed in tracked transactions.
+
+use once_cell::sync::Lazy;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::config::settings::FreshMintCacheConfig;
+use crate::dex::router::program_ids;
+use crate::positions::unix_timestamp;
+use crate::strategy::DexKind;
+
+/* --------------------------------------------------------------------- */
+/*  Mint first-seen registry                                             */
+/* --------------------------------------------------------------------- */
+
+/// Earliest observation of a mint in the stream.
+#[derive(Clone, Copy, Debug)]
+pub struct MintSighting {
+    pub slot: u64,
+    /// Unix seconds when we saw it
+    pub seen_at: u64,
+    /// Seen in the mint's create transaction, so `slot` is its real birth
+    pub from_create: bool,
+}
+
+static MINT_FIRST_SEEN: Lazy<RwLock<HashMap<Pubkey, MintSighting>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Record that `mint` appeared at `slot`. The earliest sighting is kept,
+/// except that a create transaction always wins. The registry is bounded by
+/// the fresh-mint cache's `max_cache_size`, dropping the oldest entries.
+pub fn record_mint_seen(mint: Pubkey, slot: u64, from_create: bool, cfg: &FreshMintCacheConfig) {
+    if !cfg.enabled {
+        return;
+    }
+    let sighting = MintSighting {
+        slot,
+        seen_at: unix_timestamp(),
+        from_create,
+    };
+
+    let mut seen = MINT_FIRST_SEEN.write().unwrap();
+    match seen.get_mut(&mint) {
+        Some(prev) if from_create && !prev.from_create => *prev = sighting,
+        Some(prev) if slot > 0 && (prev.slot == 0 || slot < prev.slot) && !prev.from_create => {
+            prev.slot = slot
+        }
+        Some(_) => {}
+        None => {
+            seen.insert(mint, sighting);
+        }
+    }
+
+    if seen.len() > cfg.max_cache_size {
+        // evict the oldest ~10% in one go rather than on every insert
+        let mut by_age: Vec<(Pubkey, u64)> = seen.iter().map(|(m, s)| (*m, s.seen_at)).collect();
+        by_age.sort_by_key(|(_, at)| *at);
+        let evict = (seen.len() - cfg.max_cache_size).max(cfg.max_cache_size / 10);
+        for (mint, _) in by_age.into_iter().take(evict) {
+            seen.remove(&mint);
+        }
+    }
+}
+
+/// First sighting of `mint`, if we have seen it at all.
+pub fn mint_first_seen(mint: &Pubkey) -> Option<MintSighting> {
+    MINT_FIRST_SEEN.read().unwrap().get(mint).copied()
+}
+
+/// Anchor discriminators of Pump.fun's `create` and `create_v2` (Token‑2022)
+const PUMPFUN_CREATE: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
+const PUMPFUN_CREATE_V2: [u8; 8] = [214, 144, 76, 236, 95, 139, 49, 180];
+
+/// The mint a Pump.fun `create` / `create_v2` instruction creates, or `None`
+/// for any other instruction. `accounts` are the instruction's accounts in
+/// order; the mint is the first.
+pub fn pumpfun_created_mint(program: &Pubkey, data: &[u8], accounts: &[Pubkey]) -> Option<Pubkey> {
+    if program.to_string() != program_ids::PUMPFUN_PROGRAM_ID || data.len() < 8 {
+        return None;
+    }
+    if data[..8] != PUMPFUN_CREATE && data[..8] != PUMPFUN_CREATE_V2 {
+        return None;
+    }
+    accounts.first().copied()
+}
+
+/* --------------------------------------------------------------------- */
+/*  Latest pool reserves                                                 */
+/* --------------------------------------------------------------------- */
+
+/// Reserves of a mint's pool as last seen on the stream (virtual reserves
+/// for a Pump.fun bonding curve).
+#[derive(Clone, Copy, Debug)]
+pub struct PoolReserves {
+    pub dex: DexKind,
+    pub token_reserve: u64,
+    pub sol_reserve: u64,
+    pub slot: u64,
+}
+
+static POOL_RESERVES: Lazy<RwLock<HashMap<Pubkey, PoolReserves>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Store `reserves` for `mint` unless we already have a later slot.
+pub fn record_reserves(mint: Pubkey, reserves: PoolReserves) {
+    let mut all = POOL_RESERVES.write().unwrap();
+    match all.get(&mint) {
+        Some(prev) if prev.slot > reserves.slot => {}
+        _ => {
+            all.insert(mint, reserves);
+        }
+    }
+}
+
+/// Latest known reserves of `mint`'s pool.
+pub fn latest_reserves(mint: &Pubkey) -> Option<PoolReserves> {
+    POOL_RESERVES.read().unwrap().get(mint).copied()
+}
+
+/* --------------------------------------------------------------------- */
+/*  Coin creators                                                        */
+/* --------------------------------------------------------------------- */
+
+/// Creator of `mint` as cached from decoded curve / pool state: the PumpSwap
+/// `coin_creator` first, then the Pump.fun bonding curve's creator.
+pub fn coin_creator(mint: &Pubkey) -> Option<Pubkey> {
+    get_pump_swap_coin_creator(mint).or_else(|| get_pumpfun_constants(mint).map(|c| c.creator))
+}